@@ -0,0 +1,382 @@
+//! Dispatch backends for the side-effecting `RuleAction` variants (webhook
+//! calls, database writes, watchlist updates), with bounded concurrency,
+//! exponential-backoff retries, and a per-endpoint circuit breaker so a
+//! flapping webhook can't stall the whole `execute_rules` join.
+
+use anyhow::{Context, Result};
+use sqlx::{Pool, Postgres};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock, Semaphore};
+
+use crate::rule_dsl::WatchlistAction;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+const MAX_RETRIES: u32 = 3;
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+const CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(30);
+
+/// Dispatches the side-effecting `RuleAction` variants, so `RuleExecutor`
+/// isn't hardwired to one webhook/database/watchlist backend.
+#[async_trait::async_trait]
+pub trait ActionDispatcher: Send + Sync {
+    async fn call_webhook(
+        &self,
+        url: &str,
+        method: &str,
+        headers: &serde_json::Value,
+        body: &serde_json::Value,
+    ) -> Result<()>;
+
+    async fn store_in_database(&self, table: &str, data: &serde_json::Value) -> Result<()>;
+
+    async fn update_watchlist(&self, action: &WatchlistAction, addresses: &[String]) -> Result<()>;
+}
+
+/// Addresses promoted by a rule's `UpdateWatchlist` action. Cheaply
+/// cloneable and shared with `RiskScorer`/`MEVDetector`, so a watchlist hit
+/// recorded while handling one transaction informs the scoring of the next.
+#[derive(Clone, Default)]
+pub struct Watchlist {
+    addresses: Arc<RwLock<HashSet<String>>>,
+}
+
+impl Watchlist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn contains(&self, address: &str) -> bool {
+        self.addresses.read().await.contains(&address.to_lowercase())
+    }
+
+    async fn apply(&self, action: &WatchlistAction, addresses: &[String]) {
+        let mut set = self.addresses.write().await;
+        match action {
+            WatchlistAction::Add => {
+                for address in addresses {
+                    set.insert(address.to_lowercase());
+                }
+            }
+            WatchlistAction::Remove => {
+                for address in addresses {
+                    set.remove(&address.to_lowercase());
+                }
+            }
+            WatchlistAction::Update => {
+                set.clear();
+                for address in addresses {
+                    set.insert(address.to_lowercase());
+                }
+            }
+        }
+    }
+}
+
+/// Per-endpoint circuit breaker state. Opens after `CIRCUIT_FAILURE_THRESHOLD`
+/// consecutive failures and stays open for `CIRCUIT_OPEN_DURATION`; after
+/// that it is half-open and lets calls through again, closing on the next
+/// success and reopening on the next failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct CircuitBreaker {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn allow(&mut self) -> bool {
+        match self.state {
+            CircuitState::Closed | CircuitState::HalfOpen => true,
+            CircuitState::Open => {
+                let stale = self
+                    .opened_at
+                    .is_some_and(|opened_at| opened_at.elapsed() >= CIRCUIT_OPEN_DURATION);
+                if stale {
+                    self.state = CircuitState::HalfOpen;
+                }
+                stale
+            }
+        }
+    }
+
+    fn record_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        if self.state == CircuitState::HalfOpen || self.consecutive_failures >= CIRCUIT_FAILURE_THRESHOLD {
+            self.state = CircuitState::Open;
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Production `ActionDispatcher`: `CallWebhook` performs the HTTP request,
+/// `StoreInDatabase` writes the rendered row, `UpdateWatchlist` mutates the
+/// shared `Watchlist`. Every endpoint goes through bounded concurrency,
+/// retries, and its own circuit breaker.
+pub struct DefaultActionDispatcher {
+    http: reqwest::Client,
+    db_pool: Pool<Postgres>,
+    watchlist: Watchlist,
+    semaphore: Arc<Semaphore>,
+    breakers: Mutex<HashMap<String, CircuitBreaker>>,
+}
+
+impl DefaultActionDispatcher {
+    pub fn new(db_pool: Pool<Postgres>, max_concurrent_actions: usize) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            db_pool,
+            watchlist: Watchlist::new(),
+            semaphore: Arc::new(Semaphore::new(max_concurrent_actions.max(1))),
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Shared handle `RiskScorer`/`MEVDetector` read watchlist hits from.
+    pub fn watchlist(&self) -> Watchlist {
+        self.watchlist.clone()
+    }
+
+    /// Runs `attempt` under bounded concurrency with exponential-backoff
+    /// retries, short-circuiting immediately if `endpoint`'s breaker is
+    /// open.
+    async fn with_retry<F, Fut>(&self, endpoint: &str, attempt: F) -> Result<()>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .context("action dispatch semaphore closed")?;
+
+        {
+            let mut breakers = self.breakers.lock().await;
+            if !breakers.entry(endpoint.to_string()).or_default().allow() {
+                anyhow::bail!("circuit open for action endpoint {}", endpoint);
+            }
+        }
+
+        let mut backoff = INITIAL_BACKOFF;
+        let mut last_err = None;
+
+        for remaining_attempts in (0..=MAX_RETRIES).rev() {
+            match attempt().await {
+                Ok(()) => {
+                    self.breakers
+                        .lock()
+                        .await
+                        .entry(endpoint.to_string())
+                        .or_default()
+                        .record_success();
+                    return Ok(());
+                }
+                Err(e) => {
+                    last_err = Some(e);
+                    if remaining_attempts > 0 {
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                }
+            }
+        }
+
+        self.breakers
+            .lock()
+            .await
+            .entry(endpoint.to_string())
+            .or_default()
+            .record_failure();
+
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("action dispatch to {} failed", endpoint)))
+    }
+}
+
+#[async_trait::async_trait]
+impl ActionDispatcher for DefaultActionDispatcher {
+    async fn call_webhook(
+        &self,
+        url: &str,
+        method: &str,
+        headers: &serde_json::Value,
+        body: &serde_json::Value,
+    ) -> Result<()> {
+        self.with_retry(url, || async {
+            let method: reqwest::Method = method.parse().unwrap_or(reqwest::Method::POST);
+            let mut request = self.http.request(method, url);
+
+            if let Some(header_map) = headers.as_object() {
+                for (key, value) in header_map {
+                    if let Some(value) = value.as_str() {
+                        request = request.header(key, value);
+                    }
+                }
+            }
+
+            let response = request
+                .json(body)
+                .send()
+                .await
+                .with_context(|| format!("webhook request to {} failed", url))?;
+
+            if !response.status().is_success() {
+                anyhow::bail!("webhook {} returned status {}", url, response.status());
+            }
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn store_in_database(&self, table: &str, data: &serde_json::Value) -> Result<()> {
+        if !table.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            anyhow::bail!("refusing to store into unsafe table name: {}", table);
+        }
+        let endpoint = format!("db:{}", table);
+
+        self.with_retry(&endpoint, || async {
+            let query = format!("INSERT INTO {} (data, created_at) VALUES ($1, now())", table);
+            sqlx::query(&query)
+                .bind(data)
+                .execute(&self.db_pool)
+                .await
+                .with_context(|| format!("rule action insert into {} failed", table))?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn update_watchlist(&self, action: &WatchlistAction, addresses: &[String]) -> Result<()> {
+        self.with_retry("watchlist", || async {
+            self.watchlist.apply(action, addresses).await;
+            Ok(())
+        })
+        .await
+    }
+}
+
+/// No-op `ActionDispatcher` for tests and deployments that don't wire up a
+/// real webhook/database backend - mirrors `broker::LocalBroker` standing in
+/// for a live Kafka cluster.
+#[derive(Clone, Default)]
+pub struct NoopActionDispatcher {
+    watchlist: Watchlist,
+}
+
+impl NoopActionDispatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn watchlist(&self) -> Watchlist {
+        self.watchlist.clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl ActionDispatcher for NoopActionDispatcher {
+    async fn call_webhook(
+        &self,
+        _url: &str,
+        _method: &str,
+        _headers: &serde_json::Value,
+        _body: &serde_json::Value,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    async fn store_in_database(&self, _table: &str, _data: &serde_json::Value) -> Result<()> {
+        Ok(())
+    }
+
+    async fn update_watchlist(&self, action: &WatchlistAction, addresses: &[String]) -> Result<()> {
+        self.watchlist.apply(action, addresses).await;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn watchlist_add_remove_update() {
+        let watchlist = Watchlist::new();
+
+        watchlist
+            .apply(&WatchlistAction::Add, &["0xABC".to_string(), "0xdef".to_string()])
+            .await;
+        assert!(watchlist.contains("0xabc").await);
+        assert!(watchlist.contains("0xDEF").await);
+
+        watchlist.apply(&WatchlistAction::Remove, &["0xabc".to_string()]).await;
+        assert!(!watchlist.contains("0xabc").await);
+        assert!(watchlist.contains("0xdef").await);
+
+        watchlist
+            .apply(&WatchlistAction::Update, &["0x111".to_string()])
+            .await;
+        assert!(!watchlist.contains("0xdef").await);
+        assert!(watchlist.contains("0x111").await);
+    }
+
+    #[test]
+    fn circuit_opens_after_threshold_and_recovers() {
+        let mut breaker = CircuitBreaker::default();
+        assert!(breaker.allow());
+
+        for _ in 0..CIRCUIT_FAILURE_THRESHOLD {
+            breaker.record_failure();
+        }
+        assert_eq!(breaker.state, CircuitState::Open);
+        assert!(!breaker.allow());
+
+        // Simulate the open window having elapsed.
+        breaker.opened_at = Some(Instant::now() - CIRCUIT_OPEN_DURATION);
+        assert!(breaker.allow());
+        assert_eq!(breaker.state, CircuitState::HalfOpen);
+
+        breaker.record_success();
+        assert_eq!(breaker.state, CircuitState::Closed);
+        assert!(breaker.allow());
+    }
+
+    #[tokio::test]
+    async fn noop_dispatcher_updates_its_own_watchlist() {
+        let dispatcher = NoopActionDispatcher::new();
+        dispatcher
+            .update_watchlist(&WatchlistAction::Add, &["0xabc".to_string()])
+            .await
+            .unwrap();
+
+        assert!(dispatcher.watchlist().contains("0xabc").await);
+        assert!(dispatcher.call_webhook("http://example.test", "POST", &serde_json::json!({}), &serde_json::json!({})).await.is_ok());
+        assert!(dispatcher.store_in_database("alerts_log", &serde_json::json!({})).await.is_ok());
+    }
+}
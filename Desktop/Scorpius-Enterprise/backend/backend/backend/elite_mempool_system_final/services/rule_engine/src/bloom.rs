@@ -0,0 +1,60 @@
+//! Ethereum-compatible 2048-bit logs-bloom test, used as a fast pre-filter
+//! before `RuleCondition::LogMatch` falls back to scanning a transaction's
+//! actual log entries. Mirrors the `keccak256` -> three-bit-index scheme
+//! go-ethereum uses to build `logsBloom`, so it tests correctly against
+//! blooms produced by the node rather than a bespoke encoding.
+
+use sha3::{Digest, Keccak256};
+
+const BLOOM_BYTE_LENGTH: usize = 256;
+
+/// Parses a `0x`-prefixed 512-hex-digit `logsBloom` string into its raw
+/// 256-byte form.
+pub fn parse_bloom(bloom_hex: &str) -> Option<[u8; BLOOM_BYTE_LENGTH]> {
+    let bytes = decode_hex_bytes(bloom_hex)?;
+    if bytes.len() != BLOOM_BYTE_LENGTH {
+        return None;
+    }
+    let mut bloom = [0u8; BLOOM_BYTE_LENGTH];
+    bloom.copy_from_slice(&bytes);
+    Some(bloom)
+}
+
+/// Decodes a `0x`/`0X`-prefixed (or bare) hex string into raw bytes, used
+/// both for parsing `logsBloom` and for turning an address/topic hex
+/// string into the bytes the bloom hashes over.
+pub fn decode_hex_bytes(hex: &str) -> Option<Vec<u8>> {
+    let hex = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Derives the three bit indices go-ethereum's `bloomValues` sets for
+/// `item` in a `logsBloom`: keccak256 the item, then take each of the
+/// first three 16-bit big-endian byte pairs of the hash mod 2048.
+fn bit_indices(item: &[u8]) -> [usize; 3] {
+    let hash = Keccak256::digest(item);
+    let mut indices = [0usize; 3];
+    for (i, index) in indices.iter_mut().enumerate() {
+        let pair = ((hash[2 * i] as usize) << 8) | hash[2 * i + 1] as usize;
+        *index = pair & 2047;
+    }
+    indices
+}
+
+/// Tests whether `item` (a contract address or a topic, as raw bytes)
+/// could be present in `bloom`. `false` means it's definitely absent;
+/// `true` means it's present or this is a false positive, which the
+/// caller resolves by scanning the actual log entries.
+pub fn bloom_test(bloom: &[u8; BLOOM_BYTE_LENGTH], item: &[u8]) -> bool {
+    bit_indices(item).iter().all(|&bit_index| {
+        let byte_index = BLOOM_BYTE_LENGTH - bit_index / 8 - 1;
+        let mask = 1u8 << (bit_index & 0x7);
+        bloom[byte_index] & mask == mask
+    })
+}
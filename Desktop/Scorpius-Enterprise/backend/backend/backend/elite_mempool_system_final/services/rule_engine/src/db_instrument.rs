@@ -0,0 +1,41 @@
+//! Auto-instrumented classification of `sqlx` errors, so call sites don't
+//! need to remember to invoke `Metrics::record_database_error` by hand.
+
+use crate::metrics::Metrics;
+
+/// Extension trait over `Result<T, sqlx::Error>` that classifies the failure
+/// mode, records it against `errors_total{error_type,component="database"}`,
+/// and attaches the query name as error context before propagating upward.
+pub trait InstrumentedDbResult<T> {
+    fn instrument(self, metrics: &Metrics, query_name: &str) -> anyhow::Result<T>;
+}
+
+impl<T> InstrumentedDbResult<T> for Result<T, sqlx::Error> {
+    fn instrument(self, metrics: &Metrics, query_name: &str) -> anyhow::Result<T> {
+        self.map_err(|e| {
+            let error_type = classify_sqlx_error(&e);
+            metrics.record_database_error_typed(error_type);
+            anyhow::Error::new(e).context(format!("query `{}` failed ({})", query_name, error_type))
+        })
+    }
+}
+
+/// Map a `sqlx::Error` to a stable, low-cardinality `error_type` label.
+fn classify_sqlx_error(error: &sqlx::Error) -> &'static str {
+    match error {
+        sqlx::Error::PoolTimedOut => "pool_timed_out",
+        sqlx::Error::PoolClosed => "pool_closed",
+        sqlx::Error::WorkerCrashed => "worker_crashed",
+        sqlx::Error::RowNotFound => "row_not_found",
+        sqlx::Error::ColumnNotFound(_) => "column_not_found",
+        sqlx::Error::ColumnDecode { .. } => "column_decode",
+        sqlx::Error::Io(_) => "io",
+        sqlx::Error::Database(db_err) => match db_err.code().as_deref() {
+            Some(code) if code.starts_with("23") => "constraint_violation",
+            Some(code) if code.starts_with("08") => "connection_exception",
+            Some(code) if code.starts_with("57") => "operator_intervention",
+            _ => "database_error",
+        },
+        _ => "other",
+    }
+}
@@ -0,0 +1,118 @@
+//! Dead-letter-queue subsystem for unparseable or failed transactions, so
+//! poison messages are replayable instead of silently dropped.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::broker::Producer;
+
+/// Configuration for the dead-letter queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DlqPolicy {
+    pub dlq_topic: String,
+    /// If set, the service stops consuming once more than this many invalid
+    /// messages land in a single rolling `window`.
+    pub max_invalid_per_window: Option<usize>,
+    pub window: Duration,
+}
+
+impl Default for DlqPolicy {
+    fn default() -> Self {
+        Self {
+            dlq_topic: "tx_raw.dlq".to_string(),
+            max_invalid_per_window: Some(1000),
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A message that failed to parse or process, along with enough metadata to
+/// replay or investigate it later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvalidMessage {
+    pub payload: Vec<u8>,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub reason: String,
+}
+
+/// Error returned when the rolling invalid-message count exceeds the
+/// configured threshold — a kill-switch against a bad upstream producer.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid message rate exceeded {limit} per {window:?}; halting consumption")]
+pub struct InvalidMessageRateExceeded {
+    pub limit: usize,
+    pub window: Duration,
+}
+
+/// Produces poison messages to a dedicated DLQ topic and enforces the
+/// rolling invalid-message rate limit.
+pub struct DeadLetterQueue {
+    producer: Arc<dyn Producer>,
+    policy: DlqPolicy,
+    recent_invalid: VecDeque<Instant>,
+}
+
+impl DeadLetterQueue {
+    pub fn new(producer: Arc<dyn Producer>, policy: DlqPolicy) -> Self {
+        Self {
+            producer,
+            policy,
+            recent_invalid: VecDeque::new(),
+        }
+    }
+
+    /// Produce `msg` to the DLQ topic and record it against the rolling
+    /// window. Callers must not commit the original offset until this
+    /// resolves, so a crash mid-produce doesn't lose the message.
+    pub async fn send(&mut self, msg: InvalidMessage) -> Result<()> {
+        let key = format!("{}-{}-{}", msg.topic, msg.partition, msg.offset);
+
+        self.producer
+            .send(&self.policy.dlq_topic, None, &key, &msg.payload)
+            .await?;
+
+        self.record_invalid();
+        Ok(())
+    }
+
+    /// Track this failure in the rolling window, evicting entries older than
+    /// the window. The threshold itself is enforced by `check_rate_limit`.
+    fn record_invalid(&mut self) {
+        let now = Instant::now();
+        self.recent_invalid.push_back(now);
+        self.evict_stale(now);
+    }
+
+    fn evict_stale(&mut self, now: Instant) {
+        while let Some(&oldest) = self.recent_invalid.front() {
+            if now.duration_since(oldest) > self.policy.window {
+                self.recent_invalid.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Check whether the rolling window has exceeded the configured limit.
+    /// Call after `send` so the just-recorded failure is included.
+    pub fn check_rate_limit(&mut self) -> Result<(), InvalidMessageRateExceeded> {
+        let now = Instant::now();
+        self.evict_stale(now);
+
+        if let Some(limit) = self.policy.max_invalid_per_window {
+            if self.recent_invalid.len() > limit {
+                return Err(InvalidMessageRateExceeded {
+                    limit,
+                    window: self.policy.window,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
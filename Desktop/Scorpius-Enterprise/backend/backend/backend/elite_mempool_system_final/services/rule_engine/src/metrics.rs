@@ -1,12 +1,47 @@
 use anyhow::Result;
+use netstat2::{iterate_sockets_info, AddressFamilyFlags, ProtocolFlags};
 use prometheus::{
     Counter, CounterVec, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec,
     IntGauge, IntGaugeVec, Registry,
 };
+use pin_project_lite::pin_project;
+use rdkafka::consumer::{BaseConsumer, Consumer};
+use rdkafka::{ClientConfig, Offset, TopicPartitionList};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
-use std::time::Duration;
+use std::task::{Context as TaskContext, Poll};
+use std::time::{Duration, Instant};
+use sysinfo::{DiskExt, Pid, PidExt, ProcessExt, System, SystemExt};
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::{AlertSeverity, Chain};
+
+/// Metric labels are expected to stay low-cardinality (enums, rule ids); this
+/// rejects values that look like they'd blow up series count, e.g. a raw
+/// address passed where a rule id was expected.
+const MAX_LABEL_LEN: usize = 64;
+
+fn safe_label(value: &str) -> std::borrow::Cow<'_, str> {
+    let looks_like_address = value.starts_with("0x") && value.len() >= 40;
+    if looks_like_address || value.len() > MAX_LABEL_LEN {
+        log::warn!("Rejected high-cardinality metric label value: {}", value);
+        std::borrow::Cow::Borrowed("rejected_high_cardinality")
+    } else {
+        std::borrow::Cow::Borrowed(value)
+    }
+}
 
-use crate::AlertSeverity;
+/// The label dimensions attached to a single generated alert, threaded through
+/// so per-rule/per-severity/per-chain counters stay accurate instead of
+/// collapsing into a fixed bucket.
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub rule_id: String,
+    pub severity: AlertSeverity,
+    pub chain: Chain,
+}
 
 /// Metrics collector for the rule engine
 pub struct Metrics {
@@ -15,7 +50,9 @@ pub struct Metrics {
     // Transaction processing metrics
     transactions_processed: IntCounterVec,
     transaction_processing_duration: HistogramVec,
-    
+    batch_processing_duration: Histogram,
+    end_to_end_latency: HistogramVec,
+
     // Rule execution metrics
     rules_executed: IntCounterVec,
     rule_execution_duration: HistogramVec,
@@ -28,17 +65,40 @@ pub struct Metrics {
     // Performance metrics
     active_rules_gauge: IntGauge,
     kafka_lag_gauge: GaugeVec,
+    kafka_lag_messages: IntGaugeVec,
     memory_usage_gauge: Gauge,
     cpu_usage_gauge: Gauge,
-    
+    open_fds_gauge: IntGauge,
+    tcp_sockets_gauge: IntGauge,
+    disk_total_gauge: IntGauge,
+    disk_available_gauge: IntGauge,
+    #[cfg(feature = "jemalloc")]
+    heap_allocated_gauge: IntGauge,
+    #[cfg(feature = "jemalloc")]
+    heap_active_gauge: IntGauge,
+    #[cfg(feature = "jemalloc")]
+    heap_resident_gauge: IntGauge,
+
+    // Tokio runtime saturation metrics (require `--cfg tokio_unstable`)
+    runtime_workers_gauge: IntGauge,
+    runtime_alive_tasks_gauge: IntGauge,
+    runtime_poll_count: IntCounter,
+    runtime_busy_seconds: Counter,
+    runtime_global_queue_depth_gauge: IntGauge,
+    stage_poll_duration: HistogramVec,
+
     // MEV detection metrics
     mev_patterns_detected: IntCounterVec,
     risk_scores: HistogramVec,
-    
+
     // Error metrics
     errors_total: IntCounterVec,
     kafka_errors: IntCounter,
     database_errors: IntCounter,
+
+    // Process sampling state (kept alive across ticks so CPU deltas compute correctly)
+    system: Arc<AsyncMutex<System>>,
+    pid: Pid,
 }
 
 impl Metrics {
@@ -60,7 +120,24 @@ impl Metrics {
                 "Time spent processing transactions",
             )
             .buckets(vec![0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0]),
-            &["chain"],
+            &["chain", "outcome"],
+        )?;
+
+        let batch_processing_duration = Histogram::with_opts(
+            prometheus::HistogramOpts::new(
+                "scorpius_batch_processing_duration_seconds",
+                "Time spent processing a full batch of transactions pulled off the input topic",
+            )
+            .buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0]),
+        )?;
+
+        let end_to_end_latency = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "scorpius_end_to_end_latency_seconds",
+                "Time from a transaction's own timestamp to the alert (if any) it produced being sent",
+            )
+            .buckets(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]),
+            &["chain", "outcome"],
         )?;
 
         let rules_executed = IntCounterVec::new(
@@ -77,7 +154,7 @@ impl Metrics {
                 "Time spent executing individual rules",
             )
             .buckets(vec![0.0001, 0.0005, 0.001, 0.005, 0.01, 0.025, 0.05, 0.1]),
-            &["rule_id"],
+            &["rule_id", "outcome"],
         )?;
 
         let rule_failures = IntCounterVec::new(
@@ -117,6 +194,14 @@ impl Metrics {
             &["topic", "partition"],
         )?;
 
+        let kafka_lag_messages = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "scorpius_kafka_lag_messages",
+                "Kafka consumer lag in messages (high watermark minus committed offset)",
+            ),
+            &["topic", "partition"],
+        )?;
+
         let memory_usage_gauge = Gauge::new(
             "scorpius_memory_usage_bytes",
             "Current memory usage in bytes",
@@ -127,6 +212,78 @@ impl Metrics {
             "Current CPU usage percentage",
         )?;
 
+        let open_fds_gauge = IntGauge::new(
+            "scorpius_open_fds",
+            "Number of open file descriptors held by this process",
+        )?;
+
+        let tcp_sockets_gauge = IntGauge::new(
+            "scorpius_tcp_sockets",
+            "Number of TCP sockets owned by this process",
+        )?;
+
+        let disk_total_gauge = IntGauge::new(
+            "scorpius_disk_total_bytes",
+            "Total space on the primary disk",
+        )?;
+
+        let disk_available_gauge = IntGauge::new(
+            "scorpius_disk_available_bytes",
+            "Available space on the primary disk",
+        )?;
+
+        #[cfg(feature = "jemalloc")]
+        let heap_allocated_gauge = IntGauge::new(
+            "scorpius_heap_allocated_bytes",
+            "Bytes allocated by the application as reported by jemalloc",
+        )?;
+
+        #[cfg(feature = "jemalloc")]
+        let heap_active_gauge = IntGauge::new(
+            "scorpius_heap_active_bytes",
+            "Bytes in active jemalloc pages",
+        )?;
+
+        #[cfg(feature = "jemalloc")]
+        let heap_resident_gauge = IntGauge::new(
+            "scorpius_heap_resident_bytes",
+            "Bytes of physically resident jemalloc-managed memory",
+        )?;
+
+        let runtime_workers_gauge = IntGauge::new(
+            "scorpius_tokio_workers",
+            "Number of tokio runtime worker threads",
+        )?;
+
+        let runtime_alive_tasks_gauge = IntGauge::new(
+            "scorpius_tokio_alive_tasks",
+            "Number of currently alive tokio tasks",
+        )?;
+
+        let runtime_poll_count = IntCounter::new(
+            "scorpius_tokio_poll_count_total",
+            "Total number of task polls across all workers",
+        )?;
+
+        let runtime_busy_seconds = Counter::new(
+            "scorpius_tokio_worker_busy_seconds_total",
+            "Total time tokio workers spent busy executing tasks",
+        )?;
+
+        let runtime_global_queue_depth_gauge = IntGauge::new(
+            "scorpius_tokio_global_queue_depth",
+            "Number of tasks currently in the runtime's global run queue",
+        )?;
+
+        let stage_poll_duration = HistogramVec::new(
+            prometheus::HistogramOpts::new(
+                "scorpius_pipeline_stage_poll_duration_seconds",
+                "Time spent polling a future for a given pipeline stage",
+            )
+            .buckets(vec![0.00001, 0.0001, 0.001, 0.01, 0.1, 1.0]),
+            &["stage"],
+        )?;
+
         let mev_patterns_detected = IntCounterVec::new(
             prometheus::Opts::new(
                 "scorpius_mev_patterns_detected_total",
@@ -165,6 +322,8 @@ impl Metrics {
         // Register all metrics
         registry.register(Box::new(transactions_processed.clone()))?;
         registry.register(Box::new(transaction_processing_duration.clone()))?;
+        registry.register(Box::new(batch_processing_duration.clone()))?;
+        registry.register(Box::new(end_to_end_latency.clone()))?;
         registry.register(Box::new(rules_executed.clone()))?;
         registry.register(Box::new(rule_execution_duration.clone()))?;
         registry.register(Box::new(rule_failures.clone()))?;
@@ -172,18 +331,40 @@ impl Metrics {
         registry.register(Box::new(alert_severity_distribution.clone()))?;
         registry.register(Box::new(active_rules_gauge.clone()))?;
         registry.register(Box::new(kafka_lag_gauge.clone()))?;
+        registry.register(Box::new(kafka_lag_messages.clone()))?;
         registry.register(Box::new(memory_usage_gauge.clone()))?;
         registry.register(Box::new(cpu_usage_gauge.clone()))?;
+        registry.register(Box::new(open_fds_gauge.clone()))?;
+        registry.register(Box::new(tcp_sockets_gauge.clone()))?;
+        registry.register(Box::new(disk_total_gauge.clone()))?;
+        registry.register(Box::new(disk_available_gauge.clone()))?;
+        #[cfg(feature = "jemalloc")]
+        {
+            registry.register(Box::new(heap_allocated_gauge.clone()))?;
+            registry.register(Box::new(heap_active_gauge.clone()))?;
+            registry.register(Box::new(heap_resident_gauge.clone()))?;
+        }
+        registry.register(Box::new(runtime_workers_gauge.clone()))?;
+        registry.register(Box::new(runtime_alive_tasks_gauge.clone()))?;
+        registry.register(Box::new(runtime_poll_count.clone()))?;
+        registry.register(Box::new(runtime_busy_seconds.clone()))?;
+        registry.register(Box::new(runtime_global_queue_depth_gauge.clone()))?;
+        registry.register(Box::new(stage_poll_duration.clone()))?;
         registry.register(Box::new(mev_patterns_detected.clone()))?;
         registry.register(Box::new(risk_scores.clone()))?;
         registry.register(Box::new(errors_total.clone()))?;
         registry.register(Box::new(kafka_errors.clone()))?;
         registry.register(Box::new(database_errors.clone()))?;
 
+        let pid = sysinfo::get_current_pid().map_err(|e| anyhow::anyhow!(e))?;
+        let system = Arc::new(AsyncMutex::new(System::new_all()));
+
         Ok(Self {
             registry,
             transactions_processed,
             transaction_processing_duration,
+            batch_processing_duration,
+            end_to_end_latency,
             rules_executed,
             rule_execution_duration,
             rule_failures,
@@ -191,13 +372,32 @@ impl Metrics {
             alert_severity_distribution,
             active_rules_gauge,
             kafka_lag_gauge,
+            kafka_lag_messages,
             memory_usage_gauge,
             cpu_usage_gauge,
+            open_fds_gauge,
+            tcp_sockets_gauge,
+            disk_total_gauge,
+            disk_available_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_allocated_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_active_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_resident_gauge,
+            runtime_workers_gauge,
+            runtime_alive_tasks_gauge,
+            runtime_poll_count,
+            runtime_busy_seconds,
+            runtime_global_queue_depth_gauge,
+            stage_poll_duration,
             mev_patterns_detected,
             risk_scores,
             errors_total,
             kafka_errors,
             database_errors,
+            system,
+            pid,
         })
     }
 
@@ -206,40 +406,59 @@ impl Metrics {
         &self.registry
     }
 
-    /// Record a processed transaction
+    /// Record a processed transaction and every alert it generated, with each
+    /// alert's own rule id / severity / chain rather than a fixed bucket.
     pub fn record_transaction_processed(
         &self,
+        chain: Chain,
         duration: Duration,
-        alert_count: usize,
+        alerts: &[AlertEvent],
         status: &str,
     ) {
+        let chain_label = chain.as_label();
+
         self.transactions_processed
-            .with_label_values(&["ethereum", status])
+            .with_label_values(&[chain_label, status])
             .inc();
-        
+
         self.transaction_processing_duration
-            .with_label_values(&["ethereum"])
+            .with_label_values(&[chain_label, status])
             .observe(duration.as_secs_f64());
 
-        if alert_count > 0 {
-            for _ in 0..alert_count {
-                self.alerts_generated
-                    .with_label_values(&["unknown", "medium", "ethereum"])
-                    .inc();
-            }
+        for alert in alerts {
+            let rule_label = safe_label(&alert.rule_id);
+            self.alerts_generated
+                .with_label_values(&[&rule_label, alert.severity.as_label(), alert.chain.as_label()])
+                .inc();
         }
     }
 
+    /// Record how long a full batch of transactions took to process, from
+    /// the first transaction pulled off the batch to the last alert sent.
+    pub fn record_batch_processed(&self, duration: Duration) {
+        self.batch_processing_duration.observe(duration.as_secs_f64());
+    }
+
+    /// Record the time from a transaction's own (ingestion) timestamp to the
+    /// point its outcome (an alert, or a clean pass) was produced, so
+    /// operators can see end-to-end Kafka-to-alert latency rather than just
+    /// in-process processing time.
+    pub fn record_end_to_end_latency(&self, chain: Chain, latency: Duration, outcome: &str) {
+        self.end_to_end_latency
+            .with_label_values(&[chain.as_label(), outcome])
+            .observe(latency.as_secs_f64());
+    }
+
     /// Record rule execution
     pub fn record_rule_execution(&self, rule_id: &str, duration: Duration, success: bool) {
         let status = if success { "success" } else { "failure" };
-        
+
         self.rules_executed
             .with_label_values(&[rule_id, status])
             .inc();
-        
+
         self.rule_execution_duration
-            .with_label_values(&[rule_id])
+            .with_label_values(&[rule_id, status])
             .observe(duration.as_secs_f64());
     }
 
@@ -256,15 +475,8 @@ impl Metrics {
 
     /// Record alert sent
     pub fn record_alert_sent(&self, severity: &AlertSeverity) {
-        let severity_str = match severity {
-            AlertSeverity::Low => "low",
-            AlertSeverity::Medium => "medium",
-            AlertSeverity::High => "high",
-            AlertSeverity::Critical => "critical",
-        };
-
         self.alert_severity_distribution
-            .with_label_values(&[severity_str])
+            .with_label_values(&[severity.as_label()])
             .inc();
     }
 
@@ -273,13 +485,20 @@ impl Metrics {
         self.active_rules_gauge.set(count);
     }
 
-    /// Record Kafka lag
+    /// Record Kafka lag, in estimated seconds behind the partition's high watermark
     pub fn record_kafka_lag(&self, topic: &str, partition: i32, lag_seconds: f64) {
         self.kafka_lag_gauge
             .with_label_values(&[topic, &partition.to_string()])
             .set(lag_seconds);
     }
 
+    /// Record Kafka lag, in raw messages behind the partition's high watermark
+    pub fn record_kafka_lag_messages(&self, topic: &str, partition: i32, lag_messages: i64) {
+        self.kafka_lag_messages
+            .with_label_values(&[topic, &partition.to_string()])
+            .set(lag_messages);
+    }
+
     /// Update system metrics
     pub fn update_system_metrics(&self, memory_bytes: f64, cpu_percent: f64) {
         self.memory_usage_gauge.set(memory_bytes);
@@ -316,78 +535,398 @@ impl Metrics {
             .inc();
     }
 
-    /// Start background metrics collection
-    pub async fn start_background_collection(&self) {
+    /// Record a database error with its classified failure mode (timeout,
+    /// constraint violation, io, ...) rather than a single undifferentiated
+    /// counter. Used by [`crate::db_instrument::InstrumentedDbResult`].
+    pub fn record_database_error_typed(&self, error_type: &str) {
+        self.database_errors.inc();
+        self.errors_total
+            .with_label_values(&[error_type, "database"])
+            .inc();
+    }
+
+    /// Record a failed OTLP export tick
+    pub fn record_otel_export_error(&self) {
+        self.errors_total
+            .with_label_values(&["export_failed", "otel"])
+            .inc();
+    }
+
+    /// Runs the system-resource sampling loop on the caller's task until
+    /// cancelled or panicked - callers are expected to run this under a
+    /// supervisor (see `supervisor::spawn_supervised`) that restarts it with
+    /// backoff rather than leaving the service's resource gauges frozen.
+    pub async fn run_background_collection(&self) {
         let memory_gauge = self.memory_usage_gauge.clone();
         let cpu_gauge = self.cpu_usage_gauge.clone();
+        let open_fds_gauge = self.open_fds_gauge.clone();
+        let tcp_sockets_gauge = self.tcp_sockets_gauge.clone();
+        let disk_total_gauge = self.disk_total_gauge.clone();
+        let disk_available_gauge = self.disk_available_gauge.clone();
+        #[cfg(feature = "jemalloc")]
+        let heap_allocated_gauge = self.heap_allocated_gauge.clone();
+        #[cfg(feature = "jemalloc")]
+        let heap_active_gauge = self.heap_active_gauge.clone();
+        #[cfg(feature = "jemalloc")]
+        let heap_resident_gauge = self.heap_resident_gauge.clone();
+        let runtime_workers_gauge = self.runtime_workers_gauge.clone();
+        let runtime_alive_tasks_gauge = self.runtime_alive_tasks_gauge.clone();
+        let runtime_poll_count = self.runtime_poll_count.clone();
+        let runtime_busy_seconds = self.runtime_busy_seconds.clone();
+        let runtime_global_queue_depth_gauge = self.runtime_global_queue_depth_gauge.clone();
+        let system = self.system.clone();
+        let pid = self.pid;
+        let refresh_interval = Duration::from_secs(10);
+
+        Self::run_background_collection_loop(
+            memory_gauge,
+            cpu_gauge,
+            open_fds_gauge,
+            tcp_sockets_gauge,
+            disk_total_gauge,
+            disk_available_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_allocated_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_active_gauge,
+            #[cfg(feature = "jemalloc")]
+            heap_resident_gauge,
+            runtime_workers_gauge,
+            runtime_alive_tasks_gauge,
+            runtime_poll_count,
+            runtime_busy_seconds,
+            runtime_global_queue_depth_gauge,
+            system,
+            pid,
+            refresh_interval,
+        )
+        .await;
+    }
 
-        tokio::spawn(async move {
-            let mut interval = tokio::time::interval(Duration::from_secs(10));
-            
-            loop {
-                interval.tick().await;
-                
-                // Collect system metrics
-                if let Ok(memory) = Self::get_memory_usage().await {
-                    memory_gauge.set(memory);
-                }
-                
-                if let Ok(cpu) = Self::get_cpu_usage().await {
-                    cpu_gauge.set(cpu);
+    /// The actual sampling loop, factored out of `run_background_collection`
+    /// so its parameters are owned values rather than borrows of `self`.
+    #[allow(clippy::too_many_arguments)]
+    async fn run_background_collection_loop(
+        memory_gauge: Gauge,
+        cpu_gauge: Gauge,
+        open_fds_gauge: IntGauge,
+        tcp_sockets_gauge: IntGauge,
+        disk_total_gauge: IntGauge,
+        disk_available_gauge: IntGauge,
+        #[cfg(feature = "jemalloc")] heap_allocated_gauge: IntGauge,
+        #[cfg(feature = "jemalloc")] heap_active_gauge: IntGauge,
+        #[cfg(feature = "jemalloc")] heap_resident_gauge: IntGauge,
+        runtime_workers_gauge: IntGauge,
+        runtime_alive_tasks_gauge: IntGauge,
+        runtime_poll_count: IntCounter,
+        runtime_busy_seconds: Counter,
+        runtime_global_queue_depth_gauge: IntGauge,
+        system: Arc<AsyncMutex<System>>,
+        pid: Pid,
+        refresh_interval: Duration,
+    ) {
+        // sysinfo only reports a CPU delta after two refreshes spaced apart,
+        // so prime it once before entering the regular tick loop.
+        {
+            let mut sys = system.lock().await;
+            sys.refresh_process(pid);
+        }
+        tokio::time::sleep(refresh_interval).await;
+
+        let mut interval = tokio::time::interval(refresh_interval);
+        // Tokio's runtime metrics are cumulative since process start; track
+        // the last-seen totals so the exported counters stay monotonic.
+        let mut last_poll_count: u64 = 0;
+        let mut last_busy_nanos: u64 = 0;
+
+        loop {
+            interval.tick().await;
+
+            let (memory_bytes, cpu_percent) = {
+                let mut sys = system.lock().await;
+                sys.refresh_process(pid);
+                match sys.process(pid) {
+                    Some(process) => (process.memory() * 1024, process.cpu_usage() as f64),
+                    None => (0, 0.0),
                 }
+            };
+            memory_gauge.set(memory_bytes as f64);
+            cpu_gauge.set(cpu_percent);
+
+            match Self::count_tcp_sockets(pid.as_u32()) {
+                Ok(count) => tcp_sockets_gauge.set(count as i64),
+                Err(e) => log::warn!("Failed to sample TCP socket count: {}", e),
             }
-        });
+
+            match Self::count_open_fds(pid.as_u32()) {
+                Ok(count) => open_fds_gauge.set(count as i64),
+                Err(e) => log::warn!("Failed to sample open fd count: {}", e),
+            }
+
+            {
+                let mut sys = system.lock().await;
+                sys.refresh_disks_list();
+                sys.refresh_disks();
+                let (total, available) = sys
+                    .disks()
+                    .first()
+                    .map(|disk| (disk.total_space(), disk.available_space()))
+                    .unwrap_or((0, 0));
+                disk_total_gauge.set(total as i64);
+                disk_available_gauge.set(available as i64);
+            }
+
+            #[cfg(feature = "jemalloc")]
+            Self::sample_jemalloc_stats(
+                &heap_allocated_gauge,
+                &heap_active_gauge,
+                &heap_resident_gauge,
+            );
+
+            #[cfg(tokio_unstable)]
+            Self::sample_runtime_metrics(
+                &runtime_workers_gauge,
+                &runtime_alive_tasks_gauge,
+                &runtime_poll_count,
+                &runtime_busy_seconds,
+                &runtime_global_queue_depth_gauge,
+                &mut last_poll_count,
+                &mut last_busy_nanos,
+            );
+        }
+    }
+
+    /// Sample `tokio::runtime::Handle::current().metrics()` for scheduler
+    /// saturation. Cumulative per-worker totals are diffed against the last
+    /// tick so the exported counters advance monotonically.
+    #[cfg(tokio_unstable)]
+    fn sample_runtime_metrics(
+        workers_gauge: &IntGauge,
+        alive_tasks_gauge: &IntGauge,
+        poll_count: &IntCounter,
+        busy_seconds: &Counter,
+        queue_depth_gauge: &IntGauge,
+        last_poll_count: &mut u64,
+        last_busy_nanos: &mut u64,
+    ) {
+        let handle = tokio::runtime::Handle::current();
+        let rt_metrics = handle.metrics();
+        let num_workers = rt_metrics.num_workers();
+
+        workers_gauge.set(num_workers as i64);
+        alive_tasks_gauge.set(rt_metrics.num_alive_tasks() as i64);
+        queue_depth_gauge.set(rt_metrics.global_queue_depth() as i64);
+
+        let total_polls: u64 = (0..num_workers).map(|i| rt_metrics.worker_poll_count(i)).sum();
+        let total_busy_nanos: u64 = (0..num_workers)
+            .map(|i| rt_metrics.worker_total_busy_duration(i).as_nanos() as u64)
+            .sum();
+
+        poll_count.inc_by(total_polls.saturating_sub(*last_poll_count));
+        busy_seconds.inc_by(total_busy_nanos.saturating_sub(*last_busy_nanos) as f64 / 1e9);
+
+        *last_poll_count = total_polls;
+        *last_busy_nanos = total_busy_nanos;
+    }
+
+    /// Wrap a future so each poll's wall time is observed into the
+    /// `scorpius_pipeline_stage_poll_duration_seconds` histogram under the
+    /// given pipeline stage (`ingest`, `rule_eval`, `alert_dispatch`, ...),
+    /// so operators can spot which stage is blocking the executor.
+    pub fn instrument_stage<F: Future>(&self, stage: &str, fut: F) -> StageTimed<F> {
+        let histogram = self.stage_poll_duration.with_label_values(&[stage]);
+        StageTimed { inner: fut, histogram }
+    }
+
+    /// Read jemalloc's epoch-gated stats, advancing the epoch first so the
+    /// read isn't stale.
+    #[cfg(feature = "jemalloc")]
+    fn sample_jemalloc_stats(allocated: &IntGauge, active: &IntGauge, resident: &IntGauge) {
+        use tikv_jemalloc_ctl::{epoch, stats};
+
+        if let Err(e) = epoch::advance() {
+            log::warn!("Failed to advance jemalloc epoch: {}", e);
+            return;
+        }
+
+        match (stats::allocated::read(), stats::active::read(), stats::resident::read()) {
+            (Ok(a), Ok(act), Ok(res)) => {
+                allocated.set(a as i64);
+                active.set(act as i64);
+                resident.set(res as i64);
+            }
+            _ => log::warn!("Failed to read jemalloc stats"),
+        }
+    }
+
+    /// Count TCP sockets owned by `pid` via netstat2's socket table iterator
+    fn count_tcp_sockets(pid: u32) -> Result<usize> {
+        let af_flags = AddressFamilyFlags::IPV4 | AddressFamilyFlags::IPV6;
+        let proto_flags = ProtocolFlags::TCP;
+        let sockets = iterate_sockets_info(af_flags, proto_flags)?;
+
+        Ok(sockets
+            .filter_map(|info| info.ok())
+            .filter(|info| info.associated_pids.contains(&pid))
+            .count())
     }
 
-    /// Get current memory usage (placeholder implementation)
-    async fn get_memory_usage() -> Result<f64> {
-        // In a real implementation, this would use system APIs
-        // to get actual memory usage
-        Ok(1024.0 * 1024.0 * 512.0) // 512 MB placeholder
+    /// Count open file descriptors for `pid` via /proc
+    fn count_open_fds(pid: u32) -> Result<usize> {
+        let fd_dir = format!("/proc/{}/fd", pid);
+        Ok(std::fs::read_dir(fd_dir)?.count())
     }
+}
+
+pin_project! {
+    /// Future wrapper returned by [`Metrics::instrument_stage`] that observes
+    /// each poll's wall time into a per-stage histogram.
+    pub struct StageTimed<F> {
+        #[pin]
+        inner: F,
+        histogram: Histogram,
+    }
+}
 
-    /// Get current CPU usage (placeholder implementation)
-    async fn get_cpu_usage() -> Result<f64> {
-        // In a real implementation, this would use system APIs
-        // to get actual CPU usage
-        Ok(25.5) // 25.5% placeholder
+impl<F: Future> Future for StageTimed<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let start = Instant::now();
+        let output = this.inner.poll(cx);
+        this.histogram.observe(start.elapsed().as_secs_f64());
+        output
     }
 }
 
+/// Rolling lag sample used to estimate per-partition throughput between ticks
+type RateEstimates = Arc<AsyncMutex<HashMap<(String, i32), (Instant, i64)>>>;
+
 /// Performance monitor for tracking rule engine performance
+#[derive(Clone)]
 pub struct PerformanceMonitor {
     metrics: Arc<Metrics>,
+    kafka_brokers: String,
+    kafka_group: String,
+    kafka_topics: Vec<String>,
+    rate_estimates: RateEstimates,
 }
 
 impl PerformanceMonitor {
-    pub fn new(metrics: Arc<Metrics>) -> Self {
-        Self { metrics }
+    pub fn new(metrics: Arc<Metrics>, kafka_brokers: String, kafka_group: String, kafka_topics: Vec<String>) -> Self {
+        Self {
+            metrics,
+            kafka_brokers,
+            kafka_group,
+            kafka_topics,
+            rate_estimates: Arc::new(AsyncMutex::new(HashMap::new())),
+        }
     }
 
-    /// Start performance monitoring
+    /// Start performance monitoring. System-resource sampling
+    /// (`Metrics::run_background_collection`) is started separately by the
+    /// caller under supervision, so this only spawns the Kafka consumer lag
+    /// poller.
     pub async fn start_monitoring(&self) {
-        self.metrics.start_background_collection().await;
-        
-        // Start additional monitoring tasks
-        let metrics_clone = self.metrics.clone();
+        let this = self.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(60));
-            
+
             loop {
                 interval.tick().await;
-                
-                // Monitor Kafka lag
-                if let Ok(lag) = Self::measure_kafka_lag().await {
-                    metrics_clone.record_kafka_lag("tx_raw", 0, lag);
+
+                match this.measure_kafka_lag().await {
+                    Ok(lags) => this.record_lag_metrics(lags).await,
+                    Err(e) => log::warn!("Failed to measure Kafka lag: {}", e),
                 }
             }
         });
     }
 
-    /// Measure Kafka consumer lag (placeholder implementation)
-    async fn measure_kafka_lag() -> Result<f64> {
-        // In a real implementation, this would query Kafka for consumer lag
-        Ok(0.5) // 500ms placeholder lag
+    /// Measure Kafka consumer lag for every configured topic via group metadata:
+    /// `high_watermark - committed_offset` per assigned partition.
+    async fn measure_kafka_lag(&self) -> Result<Vec<(String, i32, i64)>> {
+        let brokers = self.kafka_brokers.clone();
+        let group = self.kafka_group.clone();
+        let topics = self.kafka_topics.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<(String, i32, i64)>> {
+            let consumer: BaseConsumer = ClientConfig::new()
+                .set("bootstrap.servers", &brokers)
+                .set("group.id", &group)
+                .create()?;
+
+            let mut results = Vec::new();
+
+            for topic in &topics {
+                let metadata = consumer.fetch_metadata(Some(topic), Duration::from_secs(5))?;
+                let topic_metadata = match metadata.topics().iter().find(|t| t.name() == topic) {
+                    Some(t) => t,
+                    None => continue,
+                };
+
+                let mut tpl = TopicPartitionList::new();
+                for partition in topic_metadata.partitions() {
+                    tpl.add_partition(topic, partition.id());
+                }
+
+                let committed = consumer.committed_offsets(tpl, Duration::from_secs(5))?;
+
+                for partition in topic_metadata.partitions() {
+                    let committed_offset = committed
+                        .find_partition(topic, partition.id())
+                        .and_then(|p| match p.offset() {
+                            Offset::Offset(offset) => Some(offset),
+                            _ => None,
+                        });
+
+                    // Offset::Invalid / unassigned partitions have nothing committed yet;
+                    // skip them rather than record a meaningless negative lag.
+                    let Some(committed_offset) = committed_offset else {
+                        continue;
+                    };
+
+                    let (_low, high_watermark) =
+                        consumer.fetch_watermarks(topic, partition.id(), Duration::from_secs(5))?;
+
+                    let lag = (high_watermark - committed_offset).max(0);
+                    results.push((topic.clone(), partition.id(), lag));
+                }
+            }
+
+            Ok(results)
+        })
+        .await?
+    }
+
+    /// Record message-lag gauges and derive the estimated-seconds gauge from a
+    /// rolling per-partition messages/sec estimate.
+    async fn record_lag_metrics(&self, lags: Vec<(String, i32, i64)>) {
+        let mut rates = self.rate_estimates.lock().await;
+        let now = Instant::now();
+
+        for (topic, partition, lag_messages) in lags {
+            self.metrics
+                .record_kafka_lag_messages(&topic, partition, lag_messages);
+
+            let key = (topic.clone(), partition);
+            let messages_per_sec = match rates.get(&key) {
+                Some((last_time, last_lag)) => {
+                    let elapsed = now.duration_since(*last_time).as_secs_f64();
+                    if elapsed > 0.0 {
+                        ((last_lag - lag_messages).max(0) as f64 / elapsed).max(1.0)
+                    } else {
+                        1.0
+                    }
+                }
+                None => 1.0,
+            };
+            rates.insert(key, (now, lag_messages));
+
+            let lag_seconds = lag_messages as f64 / messages_per_sec;
+            self.metrics.record_kafka_lag(&topic, partition, lag_seconds);
+        }
     }
 }
 
@@ -398,18 +937,26 @@ mod tests {
     #[tokio::test]
     async fn test_metrics_creation() {
         let metrics = Metrics::new().unwrap();
-        
+
         // Test basic metric recording
+        let alert_events = vec![AlertEvent {
+            rule_id: "test-rule".to_string(),
+            severity: AlertSeverity::High,
+            chain: Chain::Ethereum,
+        }];
         metrics.record_transaction_processed(
+            Chain::Ethereum,
             Duration::from_millis(10),
-            1,
+            &alert_events,
             "success",
         );
-        
+
         metrics.record_alert_sent(&AlertSeverity::High);
         metrics.update_active_rules_count(5);
         metrics.record_risk_score(0.75, "ethereum");
-        
+        metrics.record_batch_processed(Duration::from_millis(250));
+        metrics.record_end_to_end_latency(Chain::Ethereum, Duration::from_millis(500), "alert");
+
         // Verify metrics are recorded (would need proper testing framework in real implementation)
         assert!(metrics.registry().gather().len() > 0);
     }
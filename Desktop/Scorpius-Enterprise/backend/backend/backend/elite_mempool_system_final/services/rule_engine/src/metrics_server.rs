@@ -0,0 +1,99 @@
+//! Prometheus exposition HTTP server, gated behind the `metrics-http` feature.
+
+use anyhow::Result;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use prometheus::{Encoder, TextEncoder};
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use crate::metrics::Metrics;
+
+/// Configuration for the built-in metrics HTTP server
+#[derive(Debug, Clone)]
+pub struct MetricsServerConfig {
+    pub listen_addr: SocketAddr,
+    pub path: String,
+}
+
+impl Default for MetricsServerConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: ([0, 0, 0, 0], 9100).into(),
+            path: "/metrics".to_string(),
+        }
+    }
+}
+
+/// Serves the Prometheus registry over HTTP so operators can scrape the rule
+/// engine directly without embedding it in a larger binary.
+pub struct MetricsServer {
+    config: MetricsServerConfig,
+    metrics: Arc<Metrics>,
+}
+
+impl MetricsServer {
+    pub fn new(config: MetricsServerConfig, metrics: Arc<Metrics>) -> Self {
+        Self { config, metrics }
+    }
+
+    /// Run the server until the process is terminated
+    pub async fn serve(self) -> Result<()> {
+        let path = Arc::new(self.config.path.clone());
+        let metrics = self.metrics;
+
+        let make_svc = make_service_fn(move |_conn| {
+            let metrics = metrics.clone();
+            let path = path.clone();
+
+            async move {
+                Ok::<_, hyper::Error>(service_fn(move |req| {
+                    let metrics = metrics.clone();
+                    let path = path.clone();
+                    async move { Ok::<_, hyper::Error>(handle_request(req, &metrics, &path)) }
+                }))
+            }
+        });
+
+        log::info!(
+            "Serving Prometheus metrics on {}{}",
+            self.config.listen_addr,
+            self.config.path
+        );
+
+        Server::bind(&self.config.listen_addr)
+            .serve(make_svc)
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn handle_request(req: Request<Body>, metrics: &Arc<Metrics>, path: &str) -> Response<Body> {
+    match (req.method(), req.uri().path()) {
+        (&Method::GET, "/health") => Response::new(Body::from("OK")),
+        (&Method::GET, p) if p == path => {
+            let encoder = TextEncoder::new();
+            let metric_families = metrics.registry().gather();
+            let mut buffer = Vec::new();
+
+            if let Err(e) = encoder.encode(&metric_families, &mut buffer) {
+                log::error!("Failed to encode metrics: {}", e);
+                return Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from("failed to encode metrics"))
+                    .unwrap();
+            }
+
+            Response::builder()
+                .status(StatusCode::OK)
+                .header("Content-Type", "text/plain; version=0.0.4")
+                .body(Body::from(buffer))
+                .unwrap()
+        }
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from("not found"))
+            .unwrap(),
+    }
+}
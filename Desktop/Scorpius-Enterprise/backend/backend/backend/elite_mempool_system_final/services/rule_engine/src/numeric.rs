@@ -0,0 +1,229 @@
+//! Minimal unsigned 256-bit integer, just wide enough to carry wei-denominated
+//! transaction values and gas prices without truncating near `u128::MAX` the
+//! way plain `.parse::<u128>()`/`.parse::<u64>()` calls do, and without
+//! pulling in a full bignum crate for this handful of call sites.
+
+use std::fmt;
+
+/// Little-endian 64-bit limbs: `limbs[0]` is the least significant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct U256 {
+    limbs: [u64; 4],
+}
+
+impl U256 {
+    pub const ZERO: U256 = U256 { limbs: [0; 4] };
+
+    pub fn from_u64(value: u64) -> Self {
+        Self {
+            limbs: [value, 0, 0, 0],
+        }
+    }
+
+    /// Parses a `0x`/`0X`-prefixed hex string or a plain decimal string,
+    /// returning `None` on malformed input or overflow past `2^256 - 1`
+    /// instead of the silent truncation `u128`/`u64::parse` would do.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(hex) => Self::from_hex(hex),
+            None => Self::from_decimal(s),
+        }
+    }
+
+    fn from_hex(hex: &str) -> Option<Self> {
+        if hex.is_empty() || hex.len() > 64 {
+            return None;
+        }
+
+        let mut padded = String::with_capacity(64);
+        for _ in 0..(64 - hex.len()) {
+            padded.push('0');
+        }
+        padded.push_str(hex);
+
+        let mut limbs = [0u64; 4];
+        for (i, chunk) in padded.as_bytes().chunks(16).rev().enumerate() {
+            let chunk_str = std::str::from_utf8(chunk).ok()?;
+            limbs[i] = u64::from_str_radix(chunk_str, 16).ok()?;
+        }
+        Some(Self { limbs })
+    }
+
+    fn from_decimal(s: &str) -> Option<Self> {
+        if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut value = U256::ZERO;
+        for byte in s.bytes() {
+            let digit = u64::from(byte - b'0');
+            value = value.checked_mul_u64(10)?.checked_add_u64(digit)?;
+        }
+        Some(value)
+    }
+
+    fn checked_add_u64(self, rhs: u64) -> Option<Self> {
+        let mut limbs = self.limbs;
+        let (sum, mut carry) = limbs[0].overflowing_add(rhs);
+        limbs[0] = sum;
+        for limb in limbs.iter_mut().skip(1) {
+            if !carry {
+                break;
+            }
+            let (sum, c) = limb.overflowing_add(1);
+            *limb = sum;
+            carry = c;
+        }
+        if carry {
+            None
+        } else {
+            Some(Self { limbs })
+        }
+    }
+
+    fn checked_mul_u64(self, rhs: u64) -> Option<Self> {
+        let mut result = [0u64; 4];
+        let mut carry: u128 = 0;
+        for i in 0..4 {
+            let product = u128::from(self.limbs[i]) * u128::from(rhs) + carry;
+            result[i] = product as u64;
+            carry = product >> 64;
+        }
+        if carry != 0 {
+            None
+        } else {
+            Some(Self { limbs: result })
+        }
+    }
+
+    /// Divides by a small `u64` divisor, returning `(quotient, remainder)`.
+    /// Used to split a wei amount into its integer/fractional parts without
+    /// ever casting the full value through `f64`.
+    pub fn div_rem_u64(self, divisor: u64) -> (Self, u64) {
+        let mut quotient = [0u64; 4];
+        let mut remainder: u128 = 0;
+        for i in (0..4).rev() {
+            let dividend = (remainder << 64) | u128::from(self.limbs[i]);
+            quotient[i] = (dividend / u128::from(divisor)) as u64;
+            remainder = dividend % u128::from(divisor);
+        }
+        (Self { limbs: quotient }, remainder as u64)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&l| l == 0)
+    }
+
+    /// Saturating truncation for the (rare) downstream math that
+    /// intentionally stays `u64`-wide, e.g. EIP-1559 gas-price arithmetic
+    /// where wei-per-gas values never approach `u64::MAX` in practice.
+    pub fn saturating_to_u64(&self) -> u64 {
+        if self.limbs[1..].iter().any(|&l| l != 0) {
+            u64::MAX
+        } else {
+            self.limbs[0]
+        }
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+
+        // 10^19 is the largest power of ten that fits in a u64; repeated
+        // division by it peels off 19-digit groups from the least
+        // significant end.
+        const CHUNK: u64 = 10_000_000_000_000_000_000;
+        let mut groups = Vec::new();
+        let mut value = *self;
+        while !value.is_zero() {
+            let (q, r) = value.div_rem_u64(CHUNK);
+            groups.push(r);
+            value = q;
+        }
+
+        let mut out = groups.pop().unwrap().to_string();
+        for group in groups.into_iter().rev() {
+            out.push_str(&format!("{:019}", group));
+        }
+        write!(f, "{}", out)
+    }
+}
+
+/// Formats a wei amount as ETH with 6 decimal places.
+pub fn format_eth(wei: U256) -> String {
+    format_fixed_point(wei, 1_000_000_000_000_000_000, 6)
+}
+
+/// Formats a wei-per-gas amount as Gwei with 2 decimal places.
+pub fn format_gwei(wei: U256) -> String {
+    format_fixed_point(wei, 1_000_000_000, 2)
+}
+
+/// Formats `value` (scaled by `scale`) as a fixed-point decimal string with
+/// `precision` digits after the point, rounding to nearest like `{:.N}`
+/// would on the equivalent `f64` division.
+fn format_fixed_point(value: U256, scale: u64, precision: u32) -> String {
+    let (integer, remainder) = value.div_rem_u64(scale);
+    let precision_scale = 10u64.pow(precision);
+
+    let rounded = (u128::from(remainder) * u128::from(precision_scale) + u128::from(scale / 2))
+        / u128::from(scale);
+
+    if rounded >= u128::from(precision_scale) {
+        let fraction = rounded - u128::from(precision_scale);
+        let integer = integer.checked_add_u64(1).unwrap_or(integer);
+        format!("{}.{:0width$}", integer, fraction, width = precision as usize)
+    } else {
+        format!("{}.{:0width$}", integer, rounded, width = precision as usize)
+    }
+}
+
+/// Parses a wei-denominated numeric transaction field (`value`, `gas`,
+/// `gas_price`), accepting both `0x`-prefixed hex and plain decimal strings.
+pub fn parse_wei(value: &str) -> Option<U256> {
+    U256::parse(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_decimal_and_hex() {
+        assert_eq!(parse_wei("1000000000000000000"), Some(U256::from_u64(1_000_000_000_000_000_000)));
+        assert_eq!(parse_wei("0xde0b6b3a7640000"), Some(U256::from_u64(1_000_000_000_000_000_000)));
+        assert_eq!(parse_wei("0X2A"), Some(U256::from_u64(42)));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(parse_wei(""), None);
+        assert_eq!(parse_wei("12x4"), None);
+        assert_eq!(parse_wei("0xzz"), None);
+    }
+
+    #[test]
+    fn survives_values_above_u128_max() {
+        // 2^196, well above u128::MAX, encoded as hex.
+        let huge = "0x10000000000000000000000000000000000000000000000000";
+        let parsed = parse_wei(huge).expect("should parse a value above u128::MAX");
+        assert!(!parsed.is_zero());
+    }
+
+    #[test]
+    fn formats_eth_and_gwei() {
+        assert_eq!(format_eth(U256::from_u64(1_500_000_000_000_000_000)), "1.500000");
+        assert_eq!(format_gwei(U256::from_u64(1_500_000_000)), "1.50");
+        assert_eq!(format_eth(U256::ZERO), "0.000000");
+    }
+
+    #[test]
+    fn rounds_fractional_part_like_float_formatting() {
+        // 1.999999999999999999 ETH rounds up to the next whole unit at 6
+        // decimal places.
+        assert_eq!(format_eth(U256::from_u64(1_999_999_999_999_999_999)), "2.000000");
+    }
+}
@@ -0,0 +1,242 @@
+//! OTLP push export, gated behind the `otel` feature. Bridges the existing
+//! `prometheus::Registry` into an OpenTelemetry meter provider so operators
+//! can push metrics to a collector instead of only scraping `/metrics`.
+
+use anyhow::Result;
+use opentelemetry::metrics::{Counter, Histogram, Meter, ObservableGauge};
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use prometheus::proto::MetricType;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::metrics::Metrics;
+
+/// Configuration for the OTLP push exporter
+#[derive(Debug, Clone)]
+pub struct OtelConfig {
+    pub endpoint: String,
+    pub push_interval: Duration,
+    pub instance_id: String,
+}
+
+impl Default for OtelConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            push_interval: Duration::from_secs(15),
+            instance_id: "rule-engine-0".to_string(),
+        }
+    }
+}
+
+/// A Prometheus label set, in a form usable as a `HashMap` key to track
+/// per-series state (last-seen counter total, last-seen histogram bucket
+/// counts) across ticks.
+type LabelKey = Vec<(String, String)>;
+
+fn label_key(metric: &prometheus::proto::Metric) -> LabelKey {
+    metric
+        .get_label()
+        .iter()
+        .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+        .collect()
+}
+
+fn attributes_for(key: &LabelKey) -> Vec<KeyValue> {
+    key.iter()
+        .map(|(name, value)| KeyValue::new(name.clone(), value.clone()))
+        .collect()
+}
+
+/// A Prometheus counter is a monotonic cumulative total; OTel's `Counter`
+/// instrument wants a delta added each report. Tracks the last-seen total
+/// per label set so only what changed since the previous tick is added.
+struct CounterState {
+    instrument: Counter<u64>,
+    last_totals: HashMap<LabelKey, u64>,
+}
+
+/// OTel's `ObservableGauge` only reports through a callback the SDK invokes
+/// on its own collection schedule, not synchronously when we'd like to
+/// push a value - so the callback instead reads whatever `values` was most
+/// recently written to by `push_once`. `_instrument` is never read
+/// directly; it's kept alive so its callback stays registered, since
+/// dropping an `ObservableGauge` deregisters it.
+struct GaugeState {
+    _instrument: ObservableGauge<f64>,
+    values: Arc<Mutex<HashMap<LabelKey, f64>>>,
+}
+
+/// A Prometheus histogram's buckets are cumulative counts; OTel's
+/// `Histogram` instrument wants individual observations recorded. Tracks
+/// the last-seen per-bucket (non-cumulative) counts per label set so only
+/// the newly-observed samples since the previous tick are recorded, each
+/// at its bucket's upper bound.
+struct HistogramState {
+    instrument: Histogram<f64>,
+    last_bucket_counts: HashMap<LabelKey, Vec<u64>>,
+}
+
+/// Periodically gathers the Prometheus registry and re-emits it as OTLP
+/// metrics, so pull-based `/metrics` scraping and push-based OTLP export
+/// coexist against the same underlying counters/gauges/histograms.
+pub struct OtelExporter {
+    config: OtelConfig,
+    metrics: Arc<Metrics>,
+}
+
+impl OtelExporter {
+    pub fn new(config: OtelConfig, metrics: Arc<Metrics>) -> Self {
+        Self { config, metrics }
+    }
+
+    /// Build the meter provider and start the background push loop
+    pub fn start(self) -> Result<()> {
+        let export_config = opentelemetry_otlp::ExportConfig {
+            endpoint: self.config.endpoint.clone(),
+            ..Default::default()
+        };
+
+        let provider = opentelemetry_otlp::new_pipeline()
+            .metrics(opentelemetry_sdk::runtime::Tokio)
+            .with_exporter(
+                opentelemetry_otlp::new_exporter()
+                    .tonic()
+                    .with_export_config(export_config),
+            )
+            .with_resource(opentelemetry_sdk::Resource::new(vec![
+                KeyValue::new("service.name", "scorpius-rule-engine"),
+                KeyValue::new("service.instance.id", self.config.instance_id.clone()),
+            ]))
+            .build()?;
+
+        let meter = provider.meter("scorpius_rule_engine");
+        let metrics = self.metrics.clone();
+        let push_interval = self.config.push_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(push_interval);
+            // Instruments are created lazily per metric family name and reused
+            // across ticks so repeated gathers don't leak new instruments.
+            let mut counters: HashMap<String, CounterState> = HashMap::new();
+            let mut gauges: HashMap<String, GaugeState> = HashMap::new();
+            let mut histograms: HashMap<String, HistogramState> = HashMap::new();
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = push_once(&meter, &metrics, &mut counters, &mut gauges, &mut histograms) {
+                    log::warn!("OTLP export tick failed: {}", e);
+                    metrics.record_otel_export_error();
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+fn push_once(
+    meter: &Meter,
+    metrics: &Arc<Metrics>,
+    counters: &mut HashMap<String, CounterState>,
+    gauges: &mut HashMap<String, GaugeState>,
+    histograms: &mut HashMap<String, HistogramState>,
+) -> Result<()> {
+    for family in metrics.registry().gather() {
+        let name = family.get_name().to_string();
+
+        for metric in family.get_metric() {
+            let key = label_key(metric);
+            let attributes = attributes_for(&key);
+
+            match family.get_field_type() {
+                MetricType::COUNTER => {
+                    let state = counters.entry(name.clone()).or_insert_with(|| CounterState {
+                        instrument: meter.u64_counter(name.clone()).init(),
+                        last_totals: HashMap::new(),
+                    });
+
+                    let current_total = metric.get_counter().get_value() as u64;
+                    let previous_total = state.last_totals.get(&key).copied().unwrap_or(0);
+                    // A total lower than last tick means the underlying
+                    // Prometheus counter was reset (process restart) -
+                    // re-baseline from zero rather than reporting whatever
+                    // a raw (saturated) subtraction would produce.
+                    let delta = if current_total < previous_total {
+                        current_total
+                    } else {
+                        current_total - previous_total
+                    };
+                    if delta > 0 {
+                        state.instrument.add(delta, &attributes);
+                    }
+                    state.last_totals.insert(key, current_total);
+                }
+                MetricType::GAUGE => {
+                    let value = metric.get_gauge().get_value();
+                    let state = gauges.entry(name.clone()).or_insert_with(|| {
+                        let values: Arc<Mutex<HashMap<LabelKey, f64>>> =
+                            Arc::new(Mutex::new(HashMap::new()));
+                        let callback_values = values.clone();
+                        let instrument = meter
+                            .f64_observable_gauge(name.clone())
+                            .with_callback(move |observer| {
+                                for (key, value) in callback_values.lock().unwrap().iter() {
+                                    observer.observe(*value, &attributes_for(key));
+                                }
+                            })
+                            .init();
+                        GaugeState {
+                            _instrument: instrument,
+                            values,
+                        }
+                    });
+                    state.values.lock().unwrap().insert(key, value);
+                }
+                MetricType::HISTOGRAM => {
+                    let buckets = metric.get_histogram().get_bucket();
+
+                    // Prometheus bucket counts are cumulative ("<= upper
+                    // bound"); convert to the count that actually fell in
+                    // each bucket's own range this scrape.
+                    let mut previous_cumulative = 0u64;
+                    let bucket_counts: Vec<u64> = buckets
+                        .iter()
+                        .map(|bucket| {
+                            let cumulative = bucket.get_cumulative_count();
+                            let count = cumulative.saturating_sub(previous_cumulative);
+                            previous_cumulative = cumulative;
+                            count
+                        })
+                        .collect();
+
+                    let state = histograms.entry(name.clone()).or_insert_with(|| HistogramState {
+                        instrument: meter.f64_histogram(name.clone()).init(),
+                        last_bucket_counts: HashMap::new(),
+                    });
+                    let previous_counts = state
+                        .last_bucket_counts
+                        .get(&key)
+                        .cloned()
+                        .unwrap_or_else(|| vec![0; bucket_counts.len()]);
+
+                    for (i, bucket) in buckets.iter().enumerate() {
+                        let previous = previous_counts.get(i).copied().unwrap_or(0);
+                        let new_samples = bucket_counts[i].saturating_sub(previous);
+                        for _ in 0..new_samples {
+                            state.instrument.record(bucket.get_upper_bound(), &attributes);
+                        }
+                    }
+
+                    state.last_bucket_counts.insert(key, bucket_counts);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
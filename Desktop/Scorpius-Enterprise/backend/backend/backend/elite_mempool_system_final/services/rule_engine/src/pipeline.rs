@@ -0,0 +1,298 @@
+//! Composable processing-strategy pipeline that replaces the hand-rolled
+//! consume/transform/produce `select!` loop. Stages are chained
+//! (`Batch` -> `RunTask` -> `CommitOffsets`), each implementing
+//! [`ProcessingStrategy`], so adding a new stage (enrichment, filtering,
+//! fan-out) is a new strategy rather than an edit to a monolithic loop.
+//!
+//! Backpressure propagates explicitly: a stage that can't accept more work
+//! right now returns `SubmitError::MessageRejected`, handing the message
+//! back to the caller instead of buffering it unboundedly. The runner is
+//! expected to stop pulling from the consumer until the rejected message
+//! can be resubmitted.
+
+use anyhow::Result;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// A single unit of work moving through the pipeline, carrying the Kafka
+/// `(partition, offset)` pair(s) it originated from so a terminal stage can
+/// commit them. A freshly-consumed message carries exactly one pair; a
+/// message produced by folding several together (e.g. `Batch`) carries the
+/// merged set, one pair per partition represented.
+#[derive(Debug, Clone)]
+pub struct PipelineMessage<T> {
+    pub payload: T,
+    pub offsets: Vec<(i32, i64)>,
+}
+
+impl<T> PipelineMessage<T> {
+    pub fn new(payload: T, partition: i32, offset: i64) -> Self {
+        Self {
+            payload,
+            offsets: vec![(partition, offset)],
+        }
+    }
+
+    pub fn with_offsets(payload: T, offsets: Vec<(i32, i64)>) -> Self {
+        Self { payload, offsets }
+    }
+}
+
+/// Merges the `(partition, offset)` pairs carried by a set of messages,
+/// keeping the highest offset seen per partition. Used by `Batch` to compute
+/// a flushed batch's commit offsets, and by callers downstream of it that
+/// need commit offsets for a subset of a batch (e.g. only the messages that
+/// actually made it out of a reordering buffer).
+pub fn merge_offsets<'a>(offset_sets: impl IntoIterator<Item = &'a [(i32, i64)]>) -> Vec<(i32, i64)> {
+    let mut highest: std::collections::BTreeMap<i32, i64> = std::collections::BTreeMap::new();
+    for offsets in offset_sets {
+        for &(partition, offset) in offsets {
+            highest
+                .entry(partition)
+                .and_modify(|existing| *existing = (*existing).max(offset))
+                .or_insert(offset);
+        }
+    }
+    highest.into_iter().collect()
+}
+
+/// Returned by `submit` when this stage (or a downstream one) cannot accept
+/// more work right now. Carries the rejected message back so the caller can
+/// retry it once capacity frees up.
+#[derive(Debug)]
+pub enum SubmitError<T> {
+    MessageRejected(PipelineMessage<T>),
+}
+
+/// A single stage in the processing pipeline.
+#[async_trait::async_trait]
+pub trait ProcessingStrategy<T: Send>: Send {
+    /// Accept a message for processing.
+    async fn submit(&mut self, message: PipelineMessage<T>) -> Result<(), SubmitError<T>>;
+
+    /// Drive any in-flight or buffered work forward without blocking on new
+    /// input. Called on every iteration of the runner loop.
+    async fn poll(&mut self) -> Result<()>;
+
+    /// Block (up to `timeout`) until all previously submitted work has
+    /// drained through this stage and everything downstream of it.
+    async fn join(&mut self, timeout: Option<Duration>) -> Result<()>;
+
+    /// Flush and release resources. No further `submit` calls follow.
+    async fn close(&mut self);
+}
+
+/// Applies a synchronous-looking (but `async`-returning) transform to each
+/// message and forwards the result downstream. The transform returns the
+/// full outgoing `PipelineMessage`, not just its payload, so it can forward
+/// the input's offsets unchanged (the common case - see
+/// `PipelineMessage::with_offsets(value, message.offsets.clone())`) or
+/// narrow them down to only what it actually emitted downstream (e.g. a
+/// transform that holds some of its input back in an internal buffer must
+/// not claim the held-back items' offsets are safe to commit). If the
+/// downstream stage rejects, the *original* message is handed back so the
+/// caller can retry it; a resubmitted message runs through the transform
+/// again.
+pub struct RunTask<T, U, F> {
+    transform: F,
+    next: Box<dyn ProcessingStrategy<U>>,
+    _marker: PhantomData<fn(T) -> U>,
+}
+
+impl<T, U, F, Fut> RunTask<T, U, F>
+where
+    F: FnMut(&PipelineMessage<T>) -> Fut + Send,
+    Fut: std::future::Future<Output = Result<PipelineMessage<U>>> + Send,
+{
+    pub fn new(transform: F, next: Box<dyn ProcessingStrategy<U>>) -> Self {
+        Self {
+            transform,
+            next,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, U, F, Fut> ProcessingStrategy<T> for RunTask<T, U, F>
+where
+    T: Send + 'static,
+    U: Send + 'static,
+    F: FnMut(&PipelineMessage<T>) -> Fut + Send,
+    Fut: std::future::Future<Output = Result<PipelineMessage<U>>> + Send,
+{
+    async fn submit(&mut self, message: PipelineMessage<T>) -> Result<(), SubmitError<T>> {
+        let next_message = match (self.transform)(&message).await {
+            Ok(value) => value,
+            Err(e) => {
+                // A transform failure is a processing error, not
+                // backpressure - log and drop rather than reject forever.
+                log::error!("pipeline stage transform failed: {}", e);
+                return Ok(());
+            }
+        };
+
+        match self.next.submit(next_message).await {
+            Ok(()) => Ok(()),
+            Err(SubmitError::MessageRejected(_)) => Err(SubmitError::MessageRejected(message)),
+        }
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        self.next.poll().await
+    }
+
+    async fn join(&mut self, timeout: Option<Duration>) -> Result<()> {
+        self.next.join(timeout).await
+    }
+
+    async fn close(&mut self) {
+        self.next.close().await
+    }
+}
+
+/// Accumulates messages up to `batch_size` or `batch_timeout`, whichever
+/// comes first, then forwards the accumulated `Vec` downstream as a single
+/// message. Replaces the manual `Vec` + `Instant` bookkeeping that used to
+/// live directly in the consume loop.
+pub struct Batch<T> {
+    buffer: Vec<PipelineMessage<T>>,
+    batch_size: usize,
+    batch_timeout: Duration,
+    last_flush: Instant,
+    /// A batch that was built but rejected downstream; retried on the next
+    /// `poll` before any new message is accepted.
+    pending: Option<PipelineMessage<Vec<PipelineMessage<T>>>>,
+    next: Box<dyn ProcessingStrategy<Vec<PipelineMessage<T>>>>,
+}
+
+impl<T> Batch<T> {
+    pub fn new(
+        batch_size: usize,
+        batch_timeout: Duration,
+        next: Box<dyn ProcessingStrategy<Vec<PipelineMessage<T>>>>,
+    ) -> Self {
+        Self {
+            buffer: Vec::new(),
+            batch_size,
+            batch_timeout,
+            last_flush: Instant::now(),
+            pending: None,
+            next,
+        }
+    }
+
+    fn should_flush(&self) -> bool {
+        !self.buffer.is_empty()
+            && (self.buffer.len() >= self.batch_size
+                || self.last_flush.elapsed() >= self.batch_timeout)
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> ProcessingStrategy<T> for Batch<T> {
+    async fn submit(&mut self, message: PipelineMessage<T>) -> Result<(), SubmitError<T>> {
+        if self.pending.is_some() {
+            return Err(SubmitError::MessageRejected(message));
+        }
+        self.buffer.push(message);
+        Ok(())
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        self.next.poll().await?;
+
+        if let Some(batch) = self.pending.take() {
+            match self.next.submit(batch).await {
+                Ok(()) => self.last_flush = Instant::now(),
+                Err(SubmitError::MessageRejected(rejected)) => self.pending = Some(rejected),
+            }
+            return Ok(());
+        }
+
+        if self.should_flush() {
+            let offsets = merge_offsets(self.buffer.iter().map(|m| m.offsets.as_slice()));
+            let batch = std::mem::take(&mut self.buffer);
+            let batch_message = PipelineMessage::with_offsets(batch, offsets);
+
+            match self.next.submit(batch_message).await {
+                Ok(()) => self.last_flush = Instant::now(),
+                Err(SubmitError::MessageRejected(rejected)) => self.pending = Some(rejected),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn join(&mut self, timeout: Option<Duration>) -> Result<()> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        loop {
+            if self.buffer.is_empty() && self.pending.is_none() {
+                break;
+            }
+            if !self.buffer.is_empty() && self.pending.is_none() {
+                let offsets = merge_offsets(self.buffer.iter().map(|m| m.offsets.as_slice()));
+                let batch = std::mem::take(&mut self.buffer);
+                self.pending = Some(PipelineMessage::with_offsets(batch, offsets));
+            }
+
+            self.poll().await?;
+
+            if let Some(deadline) = deadline {
+                if Instant::now() >= deadline {
+                    break;
+                }
+            }
+            if self.pending.is_some() || !self.buffer.is_empty() {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }
+
+        self.next.join(timeout).await
+    }
+
+    async fn close(&mut self) {
+        let _ = self.join(Some(Duration::from_secs(5))).await;
+        self.next.close().await;
+    }
+}
+
+/// Terminal stage: reports every `(partition, offset)` pair a message
+/// carries to a channel so the runner (which owns the consumer) can issue
+/// the actual `commit_message` calls once every upstream stage has
+/// acknowledged the message. A batch spanning multiple partitions reports
+/// one pair per partition, not just the last message's.
+pub struct CommitOffsets<T> {
+    sender: tokio::sync::mpsc::UnboundedSender<(i32, i64)>,
+    _marker: PhantomData<T>,
+}
+
+impl<T> CommitOffsets<T> {
+    pub fn new(sender: tokio::sync::mpsc::UnboundedSender<(i32, i64)>) -> Self {
+        Self {
+            sender,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: Send + 'static> ProcessingStrategy<T> for CommitOffsets<T> {
+    async fn submit(&mut self, message: PipelineMessage<T>) -> Result<(), SubmitError<T>> {
+        for (partition, offset) in message.offsets {
+            let _ = self.sender.send((partition, offset));
+        }
+        Ok(())
+    }
+
+    async fn poll(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn join(&mut self, _timeout: Option<Duration>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn close(&mut self) {}
+}
@@ -0,0 +1,186 @@
+//! Pooled async Redis access shared by alert dedup and stateful MEV
+//! detection, so neither pays for a fresh connection per call under the
+//! 1000-message batch size this service runs at.
+
+use anyhow::{Context, Result};
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+/// A small round-robin pool of multiplexed Redis connections. Each
+/// `ConnectionManager` already pipelines concurrent commands over a single
+/// socket and reconnects transparently, so pooling several of them - rather
+/// than sharing one, or opening a fresh connection per call - just spreads
+/// load across more sockets instead of serializing every command through
+/// one. Connections are established lazily, on first use, so constructing a
+/// pool never touches the network - only callers that actually issue a
+/// command need Redis to be reachable.
+#[derive(Clone)]
+pub struct RedisPool {
+    client: redis::Client,
+    slots: Arc<Vec<Mutex<Option<ConnectionManager>>>>,
+    next: Arc<AtomicUsize>,
+}
+
+impl RedisPool {
+    pub fn new(client: redis::Client, pool_size: usize) -> Self {
+        let pool_size = pool_size.max(1);
+        let slots = (0..pool_size).map(|_| Mutex::new(None)).collect();
+
+        Self {
+            client,
+            slots: Arc::new(slots),
+            next: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Borrow one connection from the pool, round-robin, connecting lazily
+    /// the first time a given slot is used.
+    async fn connection(&self) -> Result<ConnectionManager> {
+        let idx = self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        let mut slot = self.slots[idx].lock().await;
+
+        if let Some(conn) = slot.as_ref() {
+            return Ok(conn.clone());
+        }
+
+        let conn = self
+            .client
+            .get_tokio_connection_manager()
+            .await
+            .context("failed to establish pooled Redis connection")?;
+        *slot = Some(conn.clone());
+        Ok(conn)
+    }
+
+    /// Returns `true` the first time this `(rule_id, transaction_hash)` pair
+    /// is claimed within `ttl`, and `false` on every replay within that
+    /// window - so callers can suppress duplicate alerts for a transaction
+    /// that got re-consumed (e.g. after a rebalance or an at-least-once
+    /// redelivery).
+    pub async fn try_claim_alert(
+        &self,
+        rule_id: &str,
+        transaction_hash: &str,
+        ttl: Duration,
+    ) -> Result<bool> {
+        let key = format!("dedup:alert:{}:{}", rule_id, transaction_hash);
+        let mut conn = self.connection().await?;
+
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async(&mut conn)
+            .await
+            .context("Redis SET NX EX for alert dedup failed")?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// Record `member` under sliding-window sorted set `key` scored at
+    /// `timestamp`, evict entries older than `window`, and return every
+    /// member still in the window (including the one just inserted) so
+    /// callers can correlate across transactions in the same batch - and
+    /// across consumer restarts, since this state lives in Redis rather
+    /// than the process.
+    pub async fn record_and_window(
+        &self,
+        key: &str,
+        member: &str,
+        timestamp: i64,
+        window: Duration,
+    ) -> Result<Vec<String>> {
+        let mut conn = self.connection().await?;
+        let cutoff = timestamp - window.as_secs() as i64;
+
+        let _: () = conn
+            .zadd(key, member, timestamp)
+            .await
+            .context("Redis ZADD failed")?;
+        let _: () = conn
+            .zrembyscore(key, i64::MIN, cutoff)
+            .await
+            .context("Redis ZREMRANGEBYSCORE failed")?;
+        // The key itself should not live forever once an address goes
+        // quiet; expire it a little past the correlation window.
+        let _: () = conn
+            .expire(key, window.as_secs() as usize + 60)
+            .await
+            .context("Redis EXPIRE failed")?;
+
+        conn.zrangebyscore(key, cutoff, timestamp)
+            .await
+            .context("Redis ZRANGEBYSCORE failed")
+    }
+}
+
+/// The sliding-window correlation state `MEVDetector` needs, abstracted
+/// away from `RedisPool` so it can be exercised against an in-memory store
+/// in tests instead of requiring a live Redis, mirroring the
+/// `Consumer`/`Producer`/`LocalBroker` split in `broker.rs`.
+#[async_trait::async_trait]
+pub trait CorrelationStore: Send + Sync {
+    async fn record_and_window(
+        &self,
+        key: &str,
+        member: &str,
+        timestamp: i64,
+        window: Duration,
+    ) -> Result<Vec<String>>;
+}
+
+#[async_trait::async_trait]
+impl CorrelationStore for RedisPool {
+    async fn record_and_window(
+        &self,
+        key: &str,
+        member: &str,
+        timestamp: i64,
+        window: Duration,
+    ) -> Result<Vec<String>> {
+        RedisPool::record_and_window(self, key, member, timestamp, window).await
+    }
+}
+
+/// In-memory `CorrelationStore` so MEV correlation logic can be
+/// unit-tested without a live Redis, the same way `LocalBroker` stands in
+/// for Kafka. Each key holds its `(member, timestamp)` entries in
+/// insertion order; stale entries are evicted on every `record_and_window`
+/// call, matching `RedisPool`'s per-call expiry behavior.
+#[derive(Clone, Default)]
+pub struct LocalCorrelationStore {
+    windows: Arc<Mutex<HashMap<String, Vec<(String, i64)>>>>,
+}
+
+impl LocalCorrelationStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait::async_trait]
+impl CorrelationStore for LocalCorrelationStore {
+    async fn record_and_window(
+        &self,
+        key: &str,
+        member: &str,
+        timestamp: i64,
+        window: Duration,
+    ) -> Result<Vec<String>> {
+        let cutoff = timestamp - window.as_secs() as i64;
+        let mut windows = self.windows.lock().await;
+        let entries = windows.entry(key.to_string()).or_default();
+
+        entries.push((member.to_string(), timestamp));
+        entries.retain(|(_, ts)| *ts > cutoff);
+
+        Ok(entries.iter().map(|(member, _)| member.clone()).collect())
+    }
+}
@@ -0,0 +1,284 @@
+//! Optional reordering buffer that holds a transaction back, per
+//! `(chain_id, from)`, until its block-number/nonce predecessors have
+//! arrived - or a bounded staleness timeout elapses - so nonce- and
+//! block-ordered MEV heuristics see transactions in causal order even
+//! though Kafka only guarantees order within a single partition, and a
+//! reorg can reshuffle block numbers after the fact.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::pipeline::PipelineMessage;
+use crate::{Alert, AlertSeverity, Transaction};
+
+/// Configuration for the reordering buffer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReorderConfig {
+    /// Latency-sensitive deployments can turn this off entirely - every
+    /// transaction is then handed to the rule executor in arrival order,
+    /// same as before this buffer existed.
+    pub enabled: bool,
+    /// How long a transaction may sit in the buffer waiting for an earlier
+    /// nonce/block before it's released regardless.
+    pub max_hold: Duration,
+}
+
+impl Default for ReorderConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_hold: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Per-`(chain_id, from)` reordering state.
+#[derive(Default)]
+struct SenderState {
+    /// Messages waiting for their turn, alongside when they arrived. Kept
+    /// as full `PipelineMessage`s (not bare `Transaction`s) so the Kafka
+    /// offset a held-back transaction arrived with is still attached
+    /// whenever it's eventually released.
+    pending: Vec<(PipelineMessage<Transaction>, Instant)>,
+    /// Highest nonce already handed to the rule executor for this sender.
+    last_emitted_nonce: Option<u64>,
+    /// Highest block number already handed to the rule executor for this
+    /// sender, so a later, lower block number can be recognized as a reorg.
+    last_emitted_block: Option<i64>,
+}
+
+/// Buffers transactions per sender and releases them in
+/// `(block_number, transaction_index, nonce)` order.
+pub struct ReorderBuffer {
+    config: ReorderConfig,
+    senders: HashMap<(i64, String), SenderState>,
+}
+
+fn sort_key(transaction: &Transaction) -> (i64, i32, u64) {
+    (
+        transaction.block_number.unwrap_or(i64::MAX),
+        transaction.transaction_index.unwrap_or(i32::MAX),
+        transaction.nonce.parse::<u64>().unwrap_or(u64::MAX),
+    )
+}
+
+impl ReorderBuffer {
+    pub fn new(config: ReorderConfig) -> Self {
+        Self {
+            config,
+            senders: HashMap::new(),
+        }
+    }
+
+    /// Admit a freshly-pulled batch into the buffer and return every
+    /// message now ready for the rule executor - in causal order, each
+    /// still carrying the Kafka offset it originally arrived with - along
+    /// with any `Reorg`-tagged alerts produced along the way. Messages not
+    /// yet ready stay buffered (offset and all) for a future call, so a
+    /// caller that only commits offsets for what this returns never
+    /// acknowledges a transaction still sitting in the buffer. Returns
+    /// `messages` unchanged, with no alerts, if the buffer is disabled.
+    pub fn admit(
+        &mut self,
+        messages: Vec<PipelineMessage<Transaction>>,
+    ) -> (Vec<PipelineMessage<Transaction>>, Vec<Alert>) {
+        if !self.config.enabled {
+            return (messages, Vec::new());
+        }
+
+        let mut alerts = Vec::new();
+        let now = Instant::now();
+
+        for message in messages {
+            let key = (
+                message.payload.chain_id,
+                message.payload.from.to_lowercase(),
+            );
+            let state = self.senders.entry(key).or_default();
+
+            if let (Some(last_block), Some(block_number)) =
+                (state.last_emitted_block, message.payload.block_number)
+            {
+                if block_number < last_block {
+                    alerts.push(reorg_alert(&message.payload, last_block));
+                }
+            }
+
+            state.pending.push((message, now));
+        }
+
+        let mut ready = Vec::new();
+        for state in self.senders.values_mut() {
+            state.pending.sort_by_key(|(m, _)| sort_key(&m.payload));
+
+            let max_hold = self.config.max_hold;
+            let mut still_pending = Vec::new();
+
+            for (message, arrived_at) in state.pending.drain(..) {
+                let nonce = message.payload.nonce.parse::<u64>().ok();
+                let is_next_in_sequence = match (state.last_emitted_nonce, nonce) {
+                    (None, _) => true,
+                    (Some(_), None) => true, // can't order an unparseable nonce; release it
+                    (Some(last), Some(n)) => n == last + 1,
+                };
+                let stale = now.duration_since(arrived_at) >= max_hold;
+
+                if is_next_in_sequence || stale {
+                    if let Some(n) = nonce {
+                        state.last_emitted_nonce =
+                            Some(state.last_emitted_nonce.map_or(n, |last| last.max(n)));
+                    }
+                    if let Some(b) = message.payload.block_number {
+                        state.last_emitted_block =
+                            Some(state.last_emitted_block.map_or(b, |last| last.max(b)));
+                    }
+                    ready.push(message);
+                } else {
+                    still_pending.push((message, arrived_at));
+                }
+            }
+
+            state.pending = still_pending;
+        }
+
+        ready.sort_by_key(|m| sort_key(&m.payload));
+        (ready, alerts)
+    }
+}
+
+/// Build the `Reorg`-tagged alert emitted when a transaction for a sender
+/// arrives at a block number behind one already processed for that sender.
+/// Not tied to any user-defined rule, so `rule_id` is nil.
+fn reorg_alert(transaction: &Transaction, previous_high_block: i64) -> Alert {
+    Alert {
+        id: Uuid::new_v4(),
+        rule_id: Uuid::nil(),
+        transaction_hash: transaction.hash.clone(),
+        chain_id: transaction.chain_id,
+        severity: AlertSeverity::High,
+        title: "Reorg detected".to_string(),
+        description: format!(
+            "Transaction {} arrived at block {:?}, behind block {} already processed for this sender",
+            transaction.hash, transaction.block_number, previous_high_block
+        ),
+        metadata: serde_json::json!({
+            "tag": "Reorg",
+            "previous_high_block": previous_high_block,
+            "arrived_block": transaction.block_number,
+        }),
+        created_at: chrono::Utc::now(),
+        tags: vec!["reorg".to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(from: &str, nonce: &str, block: i64, index: i32) -> PipelineMessage<Transaction> {
+        let transaction = Transaction {
+            hash: format!("0x{}-{}", from, nonce),
+            chain_id: 1,
+            from: from.to_string(),
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "1".to_string(),
+            data: "0x".to_string(),
+            nonce: nonce.to_string(),
+            timestamp: 1_700_000_000,
+            block_number: Some(block),
+            transaction_index: Some(index),
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        };
+        PipelineMessage::new(transaction, 0, index as i64)
+    }
+
+    #[test]
+    fn releases_transactions_in_nonce_order() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig {
+            enabled: true,
+            max_hold: Duration::from_secs(5),
+        });
+
+        // Nonce 1 arrives before nonce 0, out of order.
+        let (ready, alerts) = buffer.admit(vec![tx("0xabc", "1", 100, 1), tx("0xabc", "0", 100, 0)]);
+
+        assert!(alerts.is_empty());
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].payload.nonce, "0");
+        assert_eq!(ready[1].payload.nonce, "1");
+    }
+
+    #[test]
+    fn holds_back_a_gap_until_stale() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig {
+            enabled: true,
+            max_hold: Duration::from_millis(0),
+        });
+
+        // Nonce 0 establishes the sequence; nonce 1 never arrives, so nonce
+        // 2 sits behind a gap. With a zero max_hold it is released as stale
+        // immediately instead of waiting forever.
+        buffer.admit(vec![tx("0xabc", "0", 100, 0)]);
+        let (ready, _) = buffer.admit(vec![tx("0xabc", "2", 100, 1)]);
+        assert_eq!(ready.len(), 1);
+    }
+
+    #[test]
+    fn disabled_buffer_passes_through_unsorted() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig {
+            enabled: false,
+            max_hold: Duration::from_secs(5),
+        });
+
+        let input = vec![tx("0xabc", "1", 100, 1), tx("0xabc", "0", 100, 0)];
+        let (ready, alerts) = buffer.admit(input.clone());
+
+        assert!(alerts.is_empty());
+        assert_eq!(ready[0].payload.nonce, input[0].payload.nonce);
+    }
+
+    #[test]
+    fn holds_back_transaction_keeps_its_original_offset_for_later_release() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig {
+            enabled: true,
+            max_hold: Duration::from_secs(5),
+        });
+
+        // Nonce 0 is released and establishes the sequence. Nonce 2 then
+        // arrives ahead of nonce 1 and is held back; when nonce 1 shows up
+        // later, nonce 2 must still carry the offset it originally arrived
+        // with, not the offset of whatever later batch finally released it.
+        buffer.admit(vec![tx("0xabc", "0", 100, 0)]);
+        let (ready, _) = buffer.admit(vec![tx("0xabc", "2", 100, 7)]);
+        assert!(ready.is_empty());
+
+        let (ready, _) = buffer.admit(vec![tx("0xabc", "1", 100, 3)]);
+        assert_eq!(ready.len(), 2);
+        assert_eq!(ready[0].payload.nonce, "1");
+        assert_eq!(ready[1].payload.nonce, "2");
+        assert_eq!(ready[1].offsets, vec![(0, 7)]);
+    }
+
+    #[test]
+    fn detects_reorg_on_lower_block_after_higher() {
+        let mut buffer = ReorderBuffer::new(ReorderConfig {
+            enabled: true,
+            max_hold: Duration::from_millis(0),
+        });
+
+        let (_, alerts) = buffer.admit(vec![tx("0xabc", "0", 200, 0)]);
+        assert!(alerts.is_empty());
+
+        let (ready, alerts) = buffer.admit(vec![tx("0xabc", "1", 150, 0)]);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].tags, vec!["reorg".to_string()]);
+        // The lower-block transaction is still handed onward for
+        // re-evaluation rather than being dropped.
+        assert_eq!(ready.len(), 1);
+    }
+}
@@ -2,6 +2,8 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::numeric;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Rule {
     pub id: Uuid,
@@ -35,6 +37,19 @@ pub enum RuleCondition {
         min_gas_price: Option<String>,
         max_gas_price: Option<String>,
         gas_limit_threshold: Option<String>,
+        /// Lower bound on the EIP-1559 priority tip (`effective_gas_price -
+        /// base_fee`), in wei. For type-0/1 transactions the tip is the
+        /// legacy gas price minus the base fee.
+        min_priority_fee: Option<String>,
+        /// Upper bound on the transaction's own fee cap (`maxFeePerGas` for
+        /// type-2, else the legacy `gas_price`), in wei - distinct from
+        /// `max_gas_price`, which bounds what the sender is *actually*
+        /// paying right now rather than what they're willing to pay.
+        max_fee_cap: Option<String>,
+        /// Base fee, in wei, to compute the effective gas price against.
+        /// Defaults to the base fee carried on the transaction's own raw
+        /// payload (`baseFeePerGas`) when omitted.
+        base_fee: Option<String>,
     },
     ValueThreshold {
         field: String,
@@ -64,6 +79,32 @@ pub enum RuleCondition {
         wasm_code: String,
         parameters: serde_json::Value,
     },
+    /// Composes child conditions into a boolean tree, so rules aren't
+    /// limited to the implicit top-level AND of `Rule::conditions`.
+    Group {
+        operator: LogicalOperator,
+        conditions: Vec<RuleCondition>,
+    },
+    /// Matches an emitted event log. `topics` are positional, with `None`
+    /// acting as a wildcard at that index (mirroring `eth_getLogs` filter
+    /// semantics).
+    LogMatch {
+        address: Option<String>,
+        topics: Vec<Option<String>>,
+        data_pattern: Option<String>,
+    },
+    /// Matches the transaction's EIP-2718 envelope type byte (0 = legacy,
+    /// 1 = EIP-2930 access-list, 2 = EIP-1559, 3 = EIP-4844 blob).
+    TransactionType {
+        types: Vec<u8>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum LogicalOperator {
+    And,
+    Or,
+    Not,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -141,7 +182,10 @@ pub enum WatchlistAction {
 }
 
 impl Rule {
-    /// Check if the rule should be applied to a transaction
+    /// Check if the rule should be applied to a transaction. The top-level
+    /// `conditions` list is an implicit AND, equivalent to wrapping them in
+    /// a single `RuleCondition::Group { operator: LogicalOperator::And, .. }`
+    /// - nested `Group` conditions are how a rule expresses OR/NOT logic.
     pub fn should_apply(&self, transaction: &crate::Transaction) -> bool {
         if !self.enabled {
             return false;
@@ -152,6 +196,21 @@ impl Rule {
             condition.evaluate(transaction)
         })
     }
+
+    /// `should_apply`'s counterpart for rules containing `MEVDetection`
+    /// conditions: evaluates every condition against `transaction` with
+    /// `window` available for cross-transaction correlation.
+    pub fn should_apply_in_context(
+        &self,
+        transaction: &crate::Transaction,
+        window: &crate::rule_executor::TransactionWindow,
+    ) -> bool {
+        if !self.enabled {
+            return false;
+        }
+
+        self.conditions.iter().all(|condition| condition.evaluate_in_context(transaction, window))
+    }
 }
 
 impl RuleCondition {
@@ -167,9 +226,22 @@ impl RuleCondition {
             RuleCondition::ContractCall { contract_address, function_signature, parameters: _ } => {
                 self.evaluate_contract_call(transaction, contract_address, function_signature)
             }
-            RuleCondition::GasAnalysis { min_gas_price, max_gas_price, gas_limit_threshold } => {
-                self.evaluate_gas_analysis(transaction, min_gas_price, max_gas_price, gas_limit_threshold)
-            }
+            RuleCondition::GasAnalysis {
+                min_gas_price,
+                max_gas_price,
+                gas_limit_threshold,
+                min_priority_fee,
+                max_fee_cap,
+                base_fee,
+            } => self.evaluate_gas_analysis(
+                transaction,
+                min_gas_price,
+                max_gas_price,
+                gas_limit_threshold,
+                min_priority_fee,
+                max_fee_cap,
+                base_fee,
+            ),
             RuleCondition::ValueThreshold { field, min_value, max_value } => {
                 self.evaluate_value_threshold(transaction, field, min_value, max_value)
             }
@@ -179,9 +251,10 @@ impl RuleCondition {
             RuleCondition::ChainFilter { chain_ids } => {
                 chain_ids.contains(&transaction.chain_id)
             }
-            RuleCondition::MEVDetection { sandwich_attack: _, frontrun_detection: _, backrun_detection: _, arbitrage_detection: _ } => {
-                // MEV detection logic would be implemented here
-                // For now, return false as placeholder
+            RuleCondition::MEVDetection { .. } => {
+                // MEV detection needs the transactions around this one to
+                // correlate against - see `evaluate_in_context`, which
+                // `should_apply_in_context` uses instead of this method.
                 false
             }
             RuleCondition::PatternMatch { field, pattern, regex } => {
@@ -192,9 +265,188 @@ impl RuleCondition {
                 // For now, return false as placeholder
                 false
             }
+            RuleCondition::Group { operator, conditions } => {
+                self.evaluate_group(transaction, operator, conditions)
+            }
+            RuleCondition::LogMatch { address, topics, data_pattern } => {
+                self.evaluate_log_match(transaction, address, topics, data_pattern)
+            }
+            RuleCondition::TransactionType { types } => {
+                let type_byte = crate::rule_executor::TxKind::from_raw(&transaction.raw).type_byte();
+                types.contains(&type_byte)
+            }
         }
     }
 
+    /// `evaluate`'s counterpart when a `TransactionWindow` is available:
+    /// every other condition just delegates to `evaluate`, but
+    /// `MEVDetection` uses `window` to correlate `transaction` against its
+    /// neighbors instead of returning its context-free placeholder.
+    pub fn evaluate_in_context(
+        &self,
+        transaction: &crate::Transaction,
+        window: &crate::rule_executor::TransactionWindow,
+    ) -> bool {
+        match self {
+            RuleCondition::MEVDetection {
+                sandwich_attack,
+                frontrun_detection,
+                backrun_detection,
+                arbitrage_detection,
+            } => self.evaluate_mev_detection(
+                transaction,
+                window,
+                *sandwich_attack,
+                *frontrun_detection,
+                *backrun_detection,
+                *arbitrage_detection,
+            ),
+            RuleCondition::Group { operator, conditions } => match operator {
+                LogicalOperator::And => conditions.iter().all(|c| c.evaluate_in_context(transaction, window)),
+                LogicalOperator::Or => conditions.iter().any(|c| c.evaluate_in_context(transaction, window)),
+                LogicalOperator::Not => match conditions.first() {
+                    Some(condition) => !condition.evaluate_in_context(transaction, window),
+                    None => false,
+                },
+            },
+            _ => self.evaluate(transaction),
+        }
+    }
+
+    /// Runs `detect_mev_window` over `window` and checks whether any
+    /// pattern enabled by the four flags involves `transaction` as the
+    /// victim/target or the attacker.
+    fn evaluate_mev_detection(
+        &self,
+        transaction: &crate::Transaction,
+        window: &crate::rule_executor::TransactionWindow,
+        sandwich_attack: bool,
+        frontrun_detection: bool,
+        backrun_detection: bool,
+        arbitrage_detection: bool,
+    ) -> bool {
+        let Some(tx_idx) = window.index_of(transaction) else {
+            return false;
+        };
+        let patterns = crate::rule_executor::detect_mev_window(window.as_slice());
+
+        patterns.iter().any(|pattern| match pattern {
+            crate::rule_executor::MEVPattern::SandwichAttack {
+                front_run_idx,
+                victim_idx,
+                back_run_idx,
+                ..
+            } => {
+                sandwich_attack
+                    && [*front_run_idx, *victim_idx, *back_run_idx]
+                        .into_iter()
+                        .any(|idx| idx == Some(tx_idx))
+            }
+            crate::rule_executor::MEVPattern::FrontRun { attacker_idx, target_idx, .. } => {
+                frontrun_detection && (*attacker_idx == tx_idx || *target_idx == tx_idx)
+            }
+            crate::rule_executor::MEVPattern::BackRun { attacker_idx, target_idx, .. } => {
+                backrun_detection && (*attacker_idx == tx_idx || *target_idx == tx_idx)
+            }
+            crate::rule_executor::MEVPattern::Arbitrage { leg_a_idx, leg_b_idx, .. } => {
+                arbitrage_detection && [*leg_a_idx, *leg_b_idx].into_iter().any(|idx| idx == Some(tx_idx))
+            }
+            _ => false,
+        })
+    }
+
+    fn evaluate_group(
+        &self,
+        transaction: &crate::Transaction,
+        operator: &LogicalOperator,
+        conditions: &[RuleCondition],
+    ) -> bool {
+        match operator {
+            LogicalOperator::And => conditions.iter().all(|condition| condition.evaluate(transaction)),
+            LogicalOperator::Or => conditions.iter().any(|condition| condition.evaluate(transaction)),
+            LogicalOperator::Not => {
+                // Not is strictly unary; `evaluate` has no error channel to
+                // surface a malformed rule through, so a group with more
+                // than one child negates only the first and ignores the
+                // rest, and an empty group is vacuously false.
+                match conditions.first() {
+                    Some(condition) => !condition.evaluate(transaction),
+                    None => false,
+                }
+            }
+        }
+    }
+
+    /// Matches a transaction's receipt logs against `address`/`topics`/
+    /// `data_pattern`. Tests the receipt's logs bloom first - the address
+    /// and every concrete topic must pass the bloom before the actual log
+    /// entries are scanned, so non-matching transactions are rejected
+    /// without ever touching `logs()`.
+    fn evaluate_log_match(
+        &self,
+        transaction: &crate::Transaction,
+        address: &Option<String>,
+        topics: &[Option<String>],
+        data_pattern: &Option<String>,
+    ) -> bool {
+        if let Some(bloom) = transaction.logs_bloom().and_then(crate::bloom::parse_bloom) {
+            let candidates = address.iter().chain(topics.iter().flatten());
+            for candidate in candidates {
+                let Some(bytes) = crate::bloom::decode_hex_bytes(candidate) else {
+                    continue;
+                };
+                if !crate::bloom::bloom_test(&bloom, &bytes) {
+                    return false;
+                }
+            }
+        }
+
+        transaction
+            .logs()
+            .iter()
+            .any(|log| Self::log_entry_matches(log, address, topics, data_pattern))
+    }
+
+    fn log_entry_matches(
+        log: &serde_json::Value,
+        address: &Option<String>,
+        topics: &[Option<String>],
+        data_pattern: &Option<String>,
+    ) -> bool {
+        if let Some(expected_address) = address {
+            let Some(actual) = log.get("address").and_then(|v| v.as_str()) else {
+                return false;
+            };
+            if !actual.eq_ignore_ascii_case(expected_address) {
+                return false;
+            }
+        }
+
+        let log_topics = log.get("topics").and_then(|v| v.as_array()).map(|v| v.as_slice()).unwrap_or(&[]);
+        for (index, expected_topic) in topics.iter().enumerate() {
+            let Some(expected_topic) = expected_topic else {
+                continue; // wildcard
+            };
+            let Some(actual) = log_topics.get(index).and_then(|v| v.as_str()) else {
+                return false;
+            };
+            if !actual.eq_ignore_ascii_case(expected_topic) {
+                return false;
+            }
+        }
+
+        if let Some(pattern) = data_pattern {
+            let Some(data) = log.get("data").and_then(|v| v.as_str()) else {
+                return false;
+            };
+            if !data.to_lowercase().contains(&pattern.to_lowercase()) {
+                return false;
+            }
+        }
+
+        true
+    }
+
     fn evaluate_value_comparison(
         &self,
         transaction: &crate::Transaction,
@@ -208,16 +460,16 @@ impl RuleCondition {
             ComparisonOperator::Equal => field_value == *value,
             ComparisonOperator::NotEqual => field_value != *value,
             ComparisonOperator::GreaterThan => {
-                self.compare_numeric(&field_value, value, |a, b| a > b)
+                self.compare_numeric(&field_value, value, |ord| ord == std::cmp::Ordering::Greater)
             }
             ComparisonOperator::LessThan => {
-                self.compare_numeric(&field_value, value, |a, b| a < b)
+                self.compare_numeric(&field_value, value, |ord| ord == std::cmp::Ordering::Less)
             }
             ComparisonOperator::GreaterThanOrEqual => {
-                self.compare_numeric(&field_value, value, |a, b| a >= b)
+                self.compare_numeric(&field_value, value, |ord| ord != std::cmp::Ordering::Less)
             }
             ComparisonOperator::LessThanOrEqual => {
-                self.compare_numeric(&field_value, value, |a, b| a <= b)
+                self.compare_numeric(&field_value, value, |ord| ord != std::cmp::Ordering::Greater)
             }
             ComparisonOperator::Contains => {
                 if let (Some(haystack), Some(needle)) = (field_value.as_str(), value.as_str()) {
@@ -294,33 +546,64 @@ impl RuleCondition {
         false
     }
 
+    /// Evaluates gas thresholds against the EIP-1559 effective gas price
+    /// (`min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)`) rather
+    /// than the raw `gas_price` field, so type-2 transactions are judged by
+    /// what they actually pay per gas instead of silently failing to parse.
+    /// Falls back to the legacy `gas_price` for type-0/1 transactions via
+    /// `rule_executor::GasAnalysis::compute`.
     fn evaluate_gas_analysis(
         &self,
         transaction: &crate::Transaction,
         min_gas_price: &Option<String>,
         max_gas_price: &Option<String>,
         gas_limit_threshold: &Option<String>,
+        min_priority_fee: &Option<String>,
+        max_fee_cap: &Option<String>,
+        base_fee: &Option<String>,
     ) -> bool {
-        // Parse gas price
-        let gas_price = if let Ok(price) = transaction.gas_price.parse::<u64>() {
-            price
-        } else {
-            return false;
-        };
-
-        // Check minimum gas price
+        let base_fee_wei = base_fee
+            .as_ref()
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or_else(|| crate::rule_executor::base_fee_per_gas(transaction));
+        let analysis = crate::rule_executor::GasAnalysis::compute(transaction, base_fee_wei);
+        let effective_gas_price = analysis.effective_gas_price_wei;
+
+        // Check minimum effective gas price
         if let Some(min_price_str) = min_gas_price {
             if let Ok(min_price) = min_price_str.parse::<u64>() {
-                if gas_price < min_price {
+                if effective_gas_price < min_price {
                     return false;
                 }
             }
         }
 
-        // Check maximum gas price
+        // Check maximum effective gas price
         if let Some(max_price_str) = max_gas_price {
             if let Ok(max_price) = max_price_str.parse::<u64>() {
-                if gas_price > max_price {
+                if effective_gas_price > max_price {
+                    return false;
+                }
+            }
+        }
+
+        // Check minimum priority tip (for legacy transactions, the part of
+        // the gas price above the base fee)
+        if let Some(min_priority_str) = min_priority_fee {
+            if let Ok(min_priority) = min_priority_str.parse::<u64>() {
+                if analysis.priority_tip_wei < min_priority {
+                    return false;
+                }
+            }
+        }
+
+        // Check the transaction's own fee cap, as distinct from what it's
+        // currently paying
+        if let Some(max_fee_cap_str) = max_fee_cap {
+            if let Ok(max_cap) = max_fee_cap_str.parse::<u64>() {
+                let fee_cap = crate::rule_executor::parse_hex_or_dec_u64(transaction.raw.get("maxFeePerGas"))
+                    .unwrap_or(effective_gas_price);
+                if fee_cap > max_cap {
                     return false;
                 }
             }
@@ -348,27 +631,25 @@ impl RuleCondition {
         max_value: &Option<String>,
     ) -> bool {
         let field_value = self.get_field_value(transaction, field);
-        
-        if let Some(value_str) = field_value.as_str() {
-            if let Ok(value) = value_str.parse::<u128>() {
-                if let Some(min_str) = min_value {
-                    if let Ok(min) = min_str.parse::<u128>() {
-                        if value < min {
-                            return false;
-                        }
+
+        if let Some(value) = Self::value_as_u256(&field_value) {
+            if let Some(min_str) = min_value {
+                if let Some(min) = numeric::U256::parse(min_str) {
+                    if value < min {
+                        return false;
                     }
                 }
+            }
 
-                if let Some(max_str) = max_value {
-                    if let Ok(max) = max_str.parse::<u128>() {
-                        if value > max {
-                            return false;
-                        }
+            if let Some(max_str) = max_value {
+                if let Some(max) = numeric::U256::parse(max_str) {
+                    if value > max {
+                        return false;
                     }
                 }
-
-                return true;
             }
+
+            return true;
         }
 
         false
@@ -436,6 +717,30 @@ impl RuleCondition {
             "chain_id" => serde_json::Value::Number(transaction.chain_id.into()),
             "timestamp" => serde_json::Value::Number(transaction.timestamp.into()),
             "status" => serde_json::Value::String(transaction.status.clone()),
+            "max_fee_per_gas" => crate::rule_executor::parse_hex_or_dec_u64(transaction.raw.get("maxFeePerGas"))
+                .map(serde_json::Value::from)
+                .unwrap_or(serde_json::Value::Null),
+            "max_priority_fee_per_gas" => {
+                crate::rule_executor::parse_hex_or_dec_u64(transaction.raw.get("maxPriorityFeePerGas"))
+                    .map(serde_json::Value::from)
+                    .unwrap_or(serde_json::Value::Null)
+            }
+            "base_fee_per_gas" => serde_json::Value::from(crate::rule_executor::base_fee_per_gas(transaction)),
+            "transaction_type" => serde_json::Value::String(
+                match crate::rule_executor::TxKind::from_raw(&transaction.raw) {
+                    crate::rule_executor::TxKind::Legacy => "legacy",
+                    crate::rule_executor::TxKind::AccessList => "access_list",
+                    crate::rule_executor::TxKind::DynamicFee => "dynamic_fee",
+                    crate::rule_executor::TxKind::Blob => "blob",
+                }
+                .to_string(),
+            ),
+            "access_list" => transaction.raw.get("accessList").cloned().unwrap_or(serde_json::Value::Null),
+            "blob_versioned_hashes" => transaction
+                .raw
+                .get("blobVersionedHashes")
+                .cloned()
+                .unwrap_or(serde_json::Value::Null),
             _ => {
                 // Try to get from raw data
                 transaction.raw.get(field).cloned().unwrap_or(serde_json::Value::Null)
@@ -443,21 +748,53 @@ impl RuleCondition {
         }
     }
 
+    /// Compares `a` and `b` as exact 256-bit unsigned integers - accepting
+    /// `0x`-prefixed hex, plain decimal strings, or JSON numbers - so
+    /// wei-scale amounts never round-trip through `f64` and silently lose
+    /// precision. Falls back to an `f64` comparison for values `U256` can't
+    /// represent (negative numbers, fractional values), and to lexical
+    /// string comparison only when a value genuinely isn't numeric at all.
     fn compare_numeric<F>(&self, a: &serde_json::Value, b: &serde_json::Value, op: F) -> bool
     where
-        F: Fn(f64, f64) -> bool,
+        F: Fn(std::cmp::Ordering) -> bool,
     {
-        match (a.as_f64(), b.as_f64()) {
-            (Some(a_num), Some(b_num)) => op(a_num, b_num),
-            _ => {
-                // Try string comparison for large numbers
-                if let (Some(a_str), Some(b_str)) = (a.as_str(), b.as_str()) {
-                    if let (Ok(a_big), Ok(b_big)) = (a_str.parse::<u128>(), b_str.parse::<u128>()) {
-                        return op(a_big as f64, b_big as f64);
-                    }
-                }
-                false
+        if let (Some(a_num), Some(b_num)) = (Self::value_as_u256(a), Self::value_as_u256(b)) {
+            return op(a_num.cmp(&b_num));
+        }
+
+        if let (Some(a_num), Some(b_num)) = (Self::value_as_f64(a), Self::value_as_f64(b)) {
+            if let Some(ordering) = a_num.partial_cmp(&b_num) {
+                return op(ordering);
             }
         }
+
+        if let (Some(a_str), Some(b_str)) = (a.as_str(), b.as_str()) {
+            return op(a_str.cmp(b_str));
+        }
+
+        false
+    }
+
+    /// Parses a `serde_json::Value` into a `numeric::U256`, accepting
+    /// `0x`-prefixed hex strings, plain decimal strings, and JSON numbers.
+    /// Returns `None` for negative or fractional values - `compare_numeric`
+    /// falls back to `value_as_f64` for those.
+    fn value_as_u256(value: &serde_json::Value) -> Option<numeric::U256> {
+        match value {
+            serde_json::Value::String(s) => numeric::U256::parse(s),
+            serde_json::Value::Number(n) => n.as_u64().map(numeric::U256::from_u64),
+            _ => None,
+        }
+    }
+
+    /// Parses a `serde_json::Value` into an `f64`, for numeric comparisons
+    /// involving a negative or fractional value that `value_as_u256` can't
+    /// represent exactly.
+    fn value_as_f64(value: &serde_json::Value) -> Option<f64> {
+        match value {
+            serde_json::Value::Number(n) => n.as_f64(),
+            serde_json::Value::String(s) => s.parse::<f64>().ok(),
+            _ => None,
+        }
     }
 }
@@ -1,44 +1,83 @@
 use anyhow::{Context, Result};
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::Semaphore;
 use uuid::Uuid;
 
+use crate::action_dispatcher::{ActionDispatcher, Watchlist};
+use crate::numeric;
+use crate::redis_pool::CorrelationStore;
 use crate::rule_dsl::{Rule, RuleAction};
 use crate::{Alert, AlertSeverity, Transaction};
 
+/// Known DEX router addresses (simplified list), shared between the plain
+/// DEX-interaction check and the stateful arbitrage correlation.
+const DEX_ADDRESSES: &[&str] = &[
+    "0x7a250d5630b4cf539739df2c5dacb4c659f2488d", // Uniswap V2 Router
+    "0xe592427a0aece92de3edee1f18e0157c05861564", // Uniswap V3 Router
+    "0xd9e1ce17f2641f24ae83637ab66a2cca9c378b9f", // SushiSwap Router
+    "0x1111111254fb6c44bac0bed2854e76f90643097d", // 1inch Router
+];
+
+/// Outcome of dispatching a single side-effecting `RuleAction`, so callers
+/// can observe a failure (e.g. for metrics) instead of only seeing it in a
+/// log line.
+#[derive(Debug, Clone)]
+pub struct ActionOutcome {
+    pub rule_id: Uuid,
+    pub action: &'static str,
+    pub succeeded: bool,
+    pub error: Option<String>,
+}
+
 /// Rule executor handles the execution of rules against transactions
+#[derive(Clone)]
 pub struct RuleExecutor {
     semaphore: Arc<Semaphore>,
+    action_dispatcher: Arc<dyn ActionDispatcher>,
 }
 
 impl RuleExecutor {
     /// Create a new rule executor with concurrency limit
-    pub fn new(max_concurrent_rules: usize) -> Self {
+    pub fn new(max_concurrent_rules: usize, action_dispatcher: Arc<dyn ActionDispatcher>) -> Self {
         Self {
             semaphore: Arc::new(Semaphore::new(max_concurrent_rules)),
+            action_dispatcher,
         }
     }
 
-    /// Execute all rules against a transaction
+    /// Execute all rules against a transaction. `window` supplies whatever
+    /// surrounding context (mempool-arrival or mined-block order) the
+    /// caller has assembled, so `GasAnalysis`/`Group`/etc. conditions
+    /// behave exactly as `should_apply` would, while `MEVDetection`
+    /// conditions - which `should_apply` alone can never satisfy - get to
+    /// correlate `transaction` against its neighbors via
+    /// `should_apply_in_context`.
     pub async fn execute_rules(
         &self,
         transaction: &Transaction,
         rules: &[Rule],
-    ) -> Result<Vec<Alert>> {
+        window: &TransactionWindow,
+    ) -> Result<(Vec<Alert>, Vec<ActionOutcome>)> {
         let mut alerts = Vec::new();
+        let mut outcomes = Vec::new();
         let mut handles = Vec::new();
 
         for rule in rules {
-            if rule.should_apply(transaction) {
+            if rule.should_apply_in_context(transaction, window) {
                 let rule_clone = rule.clone();
                 let transaction_clone = transaction.clone();
                 let semaphore_clone = self.semaphore.clone();
+                let action_dispatcher = self.action_dispatcher.clone();
 
                 let handle = tokio::spawn(async move {
                     let _permit = semaphore_clone.acquire().await.unwrap();
-                    Self::execute_single_rule(&rule_clone, &transaction_clone).await
+                    Self::execute_single_rule(&rule_clone, &transaction_clone, &action_dispatcher)
+                        .await
                 });
 
                 handles.push(handle);
@@ -48,18 +87,26 @@ impl RuleExecutor {
         // Wait for all rule executions to complete
         for handle in handles {
             match handle.await {
-                Ok(Ok(rule_alerts)) => alerts.extend(rule_alerts),
+                Ok(Ok((rule_alerts, rule_outcomes))) => {
+                    alerts.extend(rule_alerts);
+                    outcomes.extend(rule_outcomes);
+                }
                 Ok(Err(e)) => log::error!("Rule execution failed: {}", e),
                 Err(e) => log::error!("Rule execution task failed: {}", e),
             }
         }
 
-        Ok(alerts)
+        Ok((alerts, outcomes))
     }
 
     /// Execute a single rule against a transaction
-    async fn execute_single_rule(rule: &Rule, transaction: &Transaction) -> Result<Vec<Alert>> {
+    async fn execute_single_rule(
+        rule: &Rule,
+        transaction: &Transaction,
+        action_dispatcher: &Arc<dyn ActionDispatcher>,
+    ) -> Result<(Vec<Alert>, Vec<ActionOutcome>)> {
         let mut alerts = Vec::new();
+        let mut outcomes = Vec::new();
 
         for action in &rule.actions {
             match action {
@@ -90,29 +137,24 @@ impl RuleExecutor {
                     log::debug!("Notification action triggered for rule {}", rule.id);
                 }
                 RuleAction::StoreInDatabase { table, data } => {
-                    log::debug!(
-                        "Database storage action triggered for rule {} (table: {})",
-                        rule.id,
-                        table
-                    );
-                    // Database storage logic would be implemented here
+                    let rendered_data = Self::interpolate_value(data, transaction)?;
+                    let result = action_dispatcher
+                        .store_in_database(table, &rendered_data)
+                        .await;
+                    outcomes.push(Self::outcome(rule.id, "store_in_database", result));
                 }
-                RuleAction::CallWebhook { url, method, headers: _, body: _ } => {
-                    log::debug!(
-                        "Webhook action triggered for rule {} (URL: {}, method: {})",
-                        rule.id,
-                        url,
-                        method
-                    );
-                    // Webhook calling logic would be implemented here
+                RuleAction::CallWebhook { url, method, headers, body } => {
+                    let rendered_body = Self::interpolate_value(body, transaction)?;
+                    let result = action_dispatcher
+                        .call_webhook(url, method, headers, &rendered_body)
+                        .await;
+                    outcomes.push(Self::outcome(rule.id, "call_webhook", result));
                 }
-                RuleAction::UpdateWatchlist { action, addresses } => {
-                    log::debug!(
-                        "Watchlist update action triggered for rule {} ({:?} addresses)",
-                        rule.id,
-                        addresses.len()
-                    );
-                    // Watchlist update logic would be implemented here
+                RuleAction::UpdateWatchlist { action: watchlist_action, addresses } => {
+                    let result = action_dispatcher
+                        .update_watchlist(watchlist_action, addresses)
+                        .await;
+                    outcomes.push(Self::outcome(rule.id, "update_watchlist", result));
                 }
                 RuleAction::Custom { wasm_code: _, parameters: _ } => {
                     log::debug!("Custom WASM action triggered for rule {}", rule.id);
@@ -121,7 +163,42 @@ impl RuleExecutor {
             }
         }
 
-        Ok(alerts)
+        Ok((alerts, outcomes))
+    }
+
+    fn outcome(rule_id: Uuid, action: &'static str, result: Result<()>) -> ActionOutcome {
+        ActionOutcome {
+            rule_id,
+            action,
+            succeeded: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        }
+    }
+
+    /// Recursively interpolate every string leaf of a JSON value via
+    /// `interpolate_template`, so a `CallWebhook` body or `StoreInDatabase`
+    /// row can reference `{{hash}}`-style placeholders at any nesting depth.
+    fn interpolate_value(value: &serde_json::Value, transaction: &Transaction) -> Result<serde_json::Value> {
+        Ok(match value {
+            serde_json::Value::String(s) => {
+                serde_json::Value::String(Self::interpolate_template(s, transaction)?)
+            }
+            serde_json::Value::Array(items) => {
+                let mut rendered = Vec::with_capacity(items.len());
+                for item in items {
+                    rendered.push(Self::interpolate_value(item, transaction)?);
+                }
+                serde_json::Value::Array(rendered)
+            }
+            serde_json::Value::Object(map) => {
+                let mut rendered = serde_json::Map::with_capacity(map.len());
+                for (key, value) in map {
+                    rendered.insert(key.clone(), Self::interpolate_value(value, transaction)?);
+                }
+                serde_json::Value::Object(rendered)
+            }
+            other => other.clone(),
+        })
     }
 
     /// Interpolate template strings with transaction data
@@ -140,30 +217,228 @@ impl RuleExecutor {
         result = result.replace("{{timestamp}}", &transaction.timestamp.to_string());
         result = result.replace("{{status}}", &transaction.status);
 
-        // Replace value in ETH if it's a valid number
-        if let Ok(value_wei) = transaction.value.parse::<u128>() {
-            let value_eth = value_wei as f64 / 1e18;
-            result = result.replace("{{value_eth}}", &format!("{:.6}", value_eth));
+        // Replace value in ETH if it's a valid number. Parsed as `U256` so a
+        // value above `u128::MAX` (or hex-encoded) still templates instead of
+        // silently vanishing.
+        if let Some(value_wei) = numeric::parse_wei(&transaction.value) {
+            result = result.replace("{{value_eth}}", &numeric::format_eth(value_wei));
         }
 
-        // Replace gas price in Gwei if it's a valid number
-        if let Ok(gas_price_wei) = transaction.gas_price.parse::<u64>() {
-            let gas_price_gwei = gas_price_wei as f64 / 1e9;
-            result = result.replace("{{gas_price_gwei}}", &format!("{:.2}", gas_price_gwei));
+        // Replace gas price in Gwei if it's a valid number.
+        if let Some(gas_price_wei) = numeric::parse_wei(&transaction.gas_price) {
+            result = result.replace("{{gas_price_gwei}}", &numeric::format_gwei(gas_price_wei));
         }
 
         Ok(result)
     }
 }
 
+/// EIP-2718 typed-transaction kind, derived from the transaction's type
+/// byte, so downstream rules and the DSL can match on it without each
+/// having to parse `transaction.raw` themselves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxKind {
+    Legacy,
+    AccessList,
+    DynamicFee,
+    Blob,
+}
+
+impl TxKind {
+    pub(crate) fn from_raw(raw: &serde_json::Value) -> Self {
+        match parse_hex_or_dec_u64(raw.get("type")) {
+            Some(1) => TxKind::AccessList,
+            Some(2) => TxKind::DynamicFee,
+            Some(3) => TxKind::Blob,
+            _ => TxKind::Legacy,
+        }
+    }
+
+    /// The EIP-2718 envelope type byte this kind decodes from (0 = legacy,
+    /// 1 = EIP-2930 access-list, 2 = EIP-1559, 3 = EIP-4844 blob).
+    pub(crate) fn type_byte(self) -> u8 {
+        match self {
+            TxKind::Legacy => 0,
+            TxKind::AccessList => 1,
+            TxKind::DynamicFee => 2,
+            TxKind::Blob => 3,
+        }
+    }
+}
+
+/// Effective EIP-1559 gas price and priority tip for a transaction, computed
+/// against a given block base fee. Type-2 (dynamic-fee) and type-3 (blob)
+/// transactions both price against `maxFeePerGas`/`maxPriorityFeePerGas`;
+/// type-0/type-1 transactions (or a dynamic-fee/blob transaction missing
+/// those fields) fall back to the legacy `gas_price` field.
+pub struct GasAnalysis {
+    pub kind: TxKind,
+    /// `base_fee + min(max_priority_fee, max_fee - base_fee)`, capped at
+    /// `max_fee`.
+    pub effective_gas_price_wei: u64,
+    /// `effective_gas_price - base_fee` - the real urgency/MEV signal, since
+    /// the base fee portion is burned and paid by every transaction in the
+    /// block regardless of how urgently its sender wants to land.
+    pub priority_tip_wei: u64,
+}
+
+impl GasAnalysis {
+    pub fn compute(transaction: &Transaction, base_fee_per_gas: u64) -> Self {
+        let kind = TxKind::from_raw(&transaction.raw);
+        // Routed through `parse_wei` rather than `.parse::<u64>()` since
+        // ingestion sources sometimes hand over a hex-encoded gas price,
+        // which `.parse::<u64>()` silently fails on. Gas prices in wei never
+        // approach `u64::MAX` in practice, so truncating the parsed `U256`
+        // back down is safe.
+        let legacy_gas_price = numeric::parse_wei(&transaction.gas_price)
+            .map(|w| w.saturating_to_u64())
+            .unwrap_or(0);
+
+        let effective_gas_price_wei = if matches!(kind, TxKind::DynamicFee | TxKind::Blob) {
+            let max_fee = parse_hex_or_dec_u64(transaction.raw.get("maxFeePerGas"))
+                .unwrap_or(legacy_gas_price);
+            let max_priority_fee =
+                parse_hex_or_dec_u64(transaction.raw.get("maxPriorityFeePerGas")).unwrap_or(0);
+
+            if max_fee == 0 {
+                legacy_gas_price
+            } else {
+                let headroom = max_fee.saturating_sub(base_fee_per_gas);
+                (base_fee_per_gas + max_priority_fee.min(headroom)).min(max_fee)
+            }
+        } else {
+            legacy_gas_price
+        };
+
+        Self {
+            kind,
+            effective_gas_price_wei,
+            priority_tip_wei: effective_gas_price_wei.saturating_sub(base_fee_per_gas),
+        }
+    }
+
+    pub fn effective_gas_price_gwei(&self) -> f64 {
+        self.effective_gas_price_wei as f64 / 1e9
+    }
+
+    pub fn priority_tip_gwei(&self) -> f64 {
+        self.priority_tip_wei as f64 / 1e9
+    }
+}
+
+/// The block's base fee, read off the transaction's raw payload rather than
+/// threaded through every call site - mempool ingestion attaches the block
+/// context a pending transaction was sampled against alongside the
+/// transaction itself.
+pub(crate) fn base_fee_per_gas(transaction: &Transaction) -> u64 {
+    parse_hex_or_dec_u64(transaction.raw.get("baseFeePerGas")).unwrap_or(0)
+}
+
+/// Parses a value that may be a JSON-RPC style hex string (`"0x2"`) or a
+/// plain JSON number, since ingestion sources differ in which they use.
+pub(crate) fn parse_hex_or_dec_u64(value: Option<&serde_json::Value>) -> Option<u64> {
+    let value = value?;
+    if let Some(s) = value.as_str() {
+        return match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse::<u64>().ok(),
+        };
+    }
+    value.as_u64()
+}
+
+/// Pluggable source of intelligence about an address, so `RiskScorer` isn't
+/// hardwired to one address-reputation backend. A real deployment can
+/// inject an impl backed by an RPC node (for `has_code`) and a sanctions/
+/// threat-intel feed (for the other two).
+#[async_trait::async_trait]
+pub trait AddressIntel: Send + Sync {
+    /// Whether `address` has deployed bytecode, i.e. is a contract rather
+    /// than an externally-owned account.
+    async fn has_code(&self, address: &str) -> Result<bool>;
+    async fn is_sanctioned(&self, address: &str) -> Result<bool>;
+    async fn is_known_malicious(&self, address: &str) -> Result<bool>;
+}
+
+/// Address sets `InMemoryAddressIntel` is seeded from. Lives on `Config` so
+/// an operator can maintain a watchlist without a code change.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AddressIntelConfig {
+    #[serde(default)]
+    pub contract_addresses: Vec<String>,
+    #[serde(default)]
+    pub sanctioned_addresses: Vec<String>,
+    #[serde(default)]
+    pub known_malicious_addresses: Vec<String>,
+}
+
+impl AddressIntelConfig {
+    pub fn build(&self) -> InMemoryAddressIntel {
+        InMemoryAddressIntel::new(
+            self.contract_addresses.clone(),
+            self.sanctioned_addresses.clone(),
+            self.known_malicious_addresses.clone(),
+        )
+    }
+}
+
+/// In-memory `AddressIntel` seeded from configurable address sets. Ships as
+/// the default backend since this service has no on-chain RPC client of its
+/// own to derive `has_code` from live state.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryAddressIntel {
+    contracts: HashSet<String>,
+    sanctioned: HashSet<String>,
+    malicious: HashSet<String>,
+}
+
+impl InMemoryAddressIntel {
+    pub fn new(
+        contracts: Vec<String>,
+        sanctioned: Vec<String>,
+        malicious: Vec<String>,
+    ) -> Self {
+        let lower = |addresses: Vec<String>| -> HashSet<String> {
+            addresses.into_iter().map(|a| a.to_lowercase()).collect()
+        };
+        Self {
+            contracts: lower(contracts),
+            sanctioned: lower(sanctioned),
+            malicious: lower(malicious),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AddressIntel for InMemoryAddressIntel {
+    async fn has_code(&self, address: &str) -> Result<bool> {
+        Ok(self.contracts.contains(&address.to_lowercase()))
+    }
+
+    async fn is_sanctioned(&self, address: &str) -> Result<bool> {
+        Ok(self.sanctioned.contains(&address.to_lowercase()))
+    }
+
+    async fn is_known_malicious(&self, address: &str) -> Result<bool> {
+        Ok(self.malicious.contains(&address.to_lowercase()))
+    }
+}
+
 /// Risk scoring engine for transactions
+#[derive(Clone)]
 pub struct RiskScorer {
     // ML model would be loaded here
+    address_intel: Arc<dyn AddressIntel>,
+    /// Addresses an operator promoted via a rule's `UpdateWatchlist` action.
+    watchlist: Watchlist,
 }
 
 impl RiskScorer {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(address_intel: Arc<dyn AddressIntel>, watchlist: Watchlist) -> Self {
+        Self {
+            address_intel,
+            watchlist,
+        }
     }
 
     /// Calculate risk score for a transaction
@@ -171,25 +446,31 @@ impl RiskScorer {
         let mut score = 0.0;
 
         // Basic heuristics (would be replaced with ML model)
-        
-        // High value transactions are riskier
-        if let Ok(value) = transaction.value.parse::<u128>() {
-            let value_eth = value as f64 / 1e18;
-            if value_eth > 100.0 {
+
+        // High value transactions are riskier. Compared in wei against
+        // `U256` thresholds rather than converting to `f64` ETH, so a value
+        // above `u128::MAX` still scores instead of silently parsing to
+        // nothing.
+        if let Some(value_wei) = numeric::parse_wei(&transaction.value) {
+            let hundred_eth = numeric::parse_wei("100000000000000000000").expect("valid decimal literal");
+            let ten_eth = numeric::parse_wei("10000000000000000000").expect("valid decimal literal");
+            if value_wei > hundred_eth {
                 score += 0.3;
-            } else if value_eth > 10.0 {
+            } else if value_wei > ten_eth {
                 score += 0.1;
             }
         }
 
-        // High gas price might indicate urgency/MEV
-        if let Ok(gas_price) = transaction.gas_price.parse::<u64>() {
-            let gas_price_gwei = gas_price as f64 / 1e9;
-            if gas_price_gwei > 100.0 {
-                score += 0.2;
-            } else if gas_price_gwei > 50.0 {
-                score += 0.1;
-            }
+        // A high priority tip over base fee indicates urgency/MEV. For an
+        // EIP-1559 transaction the raw gas price (or max fee) mostly
+        // reflects the base fee everyone in the block pays, not the
+        // sender's urgency, so the tip is the real signal.
+        let gas_analysis = GasAnalysis::compute(transaction, base_fee_per_gas(transaction));
+        let priority_tip_gwei = gas_analysis.priority_tip_gwei();
+        if priority_tip_gwei > 100.0 {
+            score += 0.2;
+        } else if priority_tip_gwei > 50.0 {
+            score += 0.1;
         }
 
         // Contract interactions are potentially riskier
@@ -197,6 +478,14 @@ impl RiskScorer {
             score += 0.1;
         }
 
+        // EIP-3607: consensus rejects any transaction whose `from` has
+        // deployed code, so seeing one at all strongly implies a spoofed,
+        // replayed, or malformed transaction rather than an ordinary
+        // contract interaction.
+        if self.address_intel.has_code(&transaction.from).await? {
+            score += 0.5;
+        }
+
         // Unknown or suspicious addresses
         if self.is_suspicious_address(&transaction.from).await? {
             score += 0.4;
@@ -210,44 +499,58 @@ impl RiskScorer {
         Ok(score.min(1.0))
     }
 
-    /// Check if an address is suspicious (placeholder implementation)
-    async fn is_suspicious_address(&self, _address: &str) -> Result<bool> {
-        // In a real implementation, this would check against:
-        // - Known malicious addresses
-        // - Sanctions lists
-        // - Smart contract analysis results
-        // - Historical behavior patterns
-        Ok(false)
+    /// Check if an address is suspicious: known-sanctioned, known-malicious,
+    /// or operator-watchlisted.
+    async fn is_suspicious_address(&self, address: &str) -> Result<bool> {
+        Ok(self.address_intel.is_sanctioned(address).await?
+            || self.address_intel.is_known_malicious(address).await?
+            || self.watchlist.contains(address).await)
     }
 }
 
 /// MEV detection engine
+#[derive(Clone)]
 pub struct MEVDetector {
-    // MEV detection state and configuration
+    /// Sliding-window correlation state, shared across transactions in the
+    /// same batch and across consumer restarts. A trait object (rather than
+    /// a concrete `RedisPool`) so tests can swap in an in-memory
+    /// `LocalCorrelationStore` instead of requiring a live Redis.
+    redis: Arc<dyn CorrelationStore>,
+    /// How far back `from`/`to` history is correlated when looking for
+    /// sandwich/arbitrage patterns.
+    correlation_window: Duration,
+    /// Addresses an operator promoted via a rule's `UpdateWatchlist` action.
+    watchlist: Watchlist,
 }
 
 impl MEVDetector {
-    pub fn new() -> Self {
-        Self {}
+    pub fn new(redis: Arc<dyn CorrelationStore>, correlation_window: Duration, watchlist: Watchlist) -> Self {
+        Self {
+            redis,
+            correlation_window,
+            watchlist,
+        }
     }
 
     /// Detect potential MEV activity in a transaction
     pub async fn detect_mev(&self, transaction: &Transaction) -> Result<Vec<MEVPattern>> {
         let mut patterns = Vec::new();
+        let gas_analysis = GasAnalysis::compute(transaction, base_fee_per_gas(transaction));
+        let priority_tip_gwei = gas_analysis.priority_tip_gwei();
 
-        // Detect high gas price (potential frontrunning)
-        if let Ok(gas_price) = transaction.gas_price.parse::<u64>() {
-            let gas_price_gwei = gas_price as f64 / 1e9;
-            if gas_price_gwei > 200.0 {
-                patterns.push(MEVPattern::HighGasPrice {
-                    gas_price_gwei,
-                    threshold: 200.0,
-                });
-            }
+        // Detect a high priority tip over base fee (potential frontrunning).
+        // A high raw/effective gas price isn't itself a signal once most of
+        // it is base fee paid by every transaction in the block.
+        if priority_tip_gwei > 200.0 {
+            patterns.push(MEVPattern::HighGasPrice {
+                priority_tip_gwei,
+                threshold: 200.0,
+            });
         }
 
         // Detect DEX interactions (potential arbitrage)
-        if self.is_dex_transaction(transaction).await? {
+        let is_dex = self.is_dex_transaction(transaction).await?;
+        if is_dex {
             patterns.push(MEVPattern::DEXInteraction {
                 contract_address: transaction.to.clone(),
             });
@@ -260,29 +563,456 @@ impl MEVDetector {
             });
         }
 
+        if is_dex {
+            patterns.extend(self.detect_sandwich(transaction, priority_tip_gwei).await?);
+            patterns.extend(self.detect_arbitrage(transaction).await?);
+        }
+
+        // An operator-watchlisted counterparty is independently worth
+        // surfacing, regardless of which other patterns matched.
+        for address in [&transaction.from, &transaction.to] {
+            if self.watchlist.contains(address).await {
+                patterns.push(MEVPattern::WatchlistHit {
+                    address: address.clone(),
+                });
+            }
+        }
+
         Ok(patterns)
     }
 
     /// Check if transaction is interacting with a known DEX
     async fn is_dex_transaction(&self, transaction: &Transaction) -> Result<bool> {
-        // Known DEX contract addresses (simplified list)
-        let dex_addresses = vec![
-            "0x7a250d5630b4cf539739df2c5dacb4c659f2488d", // Uniswap V2 Router
-            "0xe592427a0aece92de3edee1f18e0157c05861564", // Uniswap V3 Router
-            "0xd9e1ce17f2641f24ae83637ab66a2cca9c378b9f", // SushiSwap Router
-            "0x1111111254fb6c44bac0bed2854e76f90643097d", // 1inch Router
-        ];
-
-        Ok(dex_addresses
+        Ok(Self::known_dex(&transaction.to))
+    }
+
+    fn known_dex(address: &str) -> bool {
+        DEX_ADDRESSES
             .iter()
-            .any(|addr| addr.to_lowercase() == transaction.to.to_lowercase()))
+            .any(|addr| addr.eq_ignore_ascii_case(address))
     }
+
+    /// Look for a recent transaction to the same contract, from a different
+    /// address, at a materially higher priority tip - the shape of a
+    /// front-run this transaction may be getting sandwiched between.
+    /// Correlation state is a Redis sorted set keyed by `to` address so it
+    /// spans the whole batch, and restarts, rather than just this one call.
+    async fn detect_sandwich(
+        &self,
+        transaction: &Transaction,
+        priority_tip_gwei: f64,
+    ) -> Result<Vec<MEVPattern>> {
+        let mut patterns = Vec::new();
+        let key = format!("mev:to:{}", transaction.to.to_lowercase());
+        let member = format!(
+            "{}|{}|{}",
+            transaction.hash,
+            transaction.from.to_lowercase(),
+            priority_tip_gwei
+        );
+
+        let recent = self
+            .redis
+            .record_and_window(&key, &member, transaction.timestamp, self.correlation_window)
+            .await?;
+
+        for entry in &recent {
+            let mut parts = entry.splitn(3, '|');
+            let (Some(hash), Some(from), Some(tip_str)) = (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            if hash == transaction.hash || from == transaction.from.to_lowercase() {
+                continue;
+            }
+            let Ok(other_tip) = tip_str.parse::<f64>() else {
+                continue;
+            };
+
+            if other_tip > priority_tip_gwei * 1.5 {
+                patterns.push(MEVPattern::SandwichAttack {
+                    victim_tx: transaction.hash.clone(),
+                    profit_estimate: 0.0,
+                    // Redis-correlated detection only has the two hashes
+                    // involved, not their position in a known window.
+                    front_run_idx: None,
+                    victim_idx: None,
+                    back_run_idx: None,
+                });
+                break;
+            }
+        }
+
+        Ok(patterns)
+    }
+
+    /// Look for the same `from` address hitting a second known DEX within
+    /// the correlation window - the shape of an arbitrage bot moving a
+    /// position across venues. Correlation state is a Redis sorted set keyed
+    /// by `from` address.
+    async fn detect_arbitrage(&self, transaction: &Transaction) -> Result<Vec<MEVPattern>> {
+        let mut patterns = Vec::new();
+        let key = format!("mev:from:{}", transaction.from.to_lowercase());
+        let member = format!("{}|{}", transaction.hash, transaction.to.to_lowercase());
+
+        let recent = self
+            .redis
+            .record_and_window(&key, &member, transaction.timestamp, self.correlation_window)
+            .await?;
+
+        for entry in &recent {
+            let mut parts = entry.splitn(2, '|');
+            let (Some(hash), Some(to)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+            if hash == transaction.hash || to.eq_ignore_ascii_case(&transaction.to) {
+                continue;
+            }
+            if Self::known_dex(to) {
+                patterns.push(MEVPattern::Arbitrage {
+                    dex_a: transaction.to.to_lowercase(),
+                    dex_b: to.to_string(),
+                    token: "unknown".to_string(),
+                    profit_estimate: 0.0,
+                    leg_a_idx: None,
+                    leg_b_idx: None,
+                });
+                break;
+            }
+        }
+
+        Ok(patterns)
+    }
+
+    /// Reconstruct multi-transaction MEV patterns over an ordered block (or
+    /// mempool window), by decoding each DEX-router call's token path and
+    /// grouping by pool and token. This is a sharper, calldata-level
+    /// complement to `detect_mev`'s single-transaction, Redis-correlated
+    /// heuristics - it doesn't need shared state since the whole window is
+    /// given up front, but it only sees the calls it knows how to decode.
+    pub fn detect_mev_block(&self, txs: &[Transaction]) -> Vec<MEVPattern> {
+        detect_mev_window(txs)
+    }
+
+    /// For each decoded swap, treat it as a possible sandwich victim: look
+    /// for the same pool's most recent prior buy of the victim's output
+    /// token by a different sender (the front-run), then the same sender's
+    /// next sell of that token back on the same pool (the back-run).
+    fn detect_block_sandwiches(swaps: &[(usize, &Transaction, DecodedSwap)]) -> Vec<MEVPattern> {
+        let mut patterns = Vec::new();
+
+        for &(victim_idx, victim_tx, ref victim_swap) in swaps {
+            let pool = victim_tx.to.to_lowercase();
+            let Some(token) = victim_swap.token_path.last().map(|t| t.to_lowercase()) else {
+                continue;
+            };
+
+            let mut front_run: Option<(String, usize)> = None;
+            for &(idx, tx, ref swap) in swaps {
+                if idx >= victim_idx
+                    || tx.to.to_lowercase() != pool
+                    || tx.from.to_lowercase() == victim_tx.from.to_lowercase()
+                    || swap.token_path.last().map(|t| t.to_lowercase()) != Some(token.clone())
+                {
+                    continue;
+                }
+                if front_run.as_ref().map_or(true, |(_, front_idx)| idx > *front_idx) {
+                    front_run = Some((tx.from.to_lowercase(), idx));
+                }
+            }
+
+            let Some((attacker, front_idx)) = front_run else {
+                continue;
+            };
+
+            // Earliest later sell of `token` back on the same pool by the
+            // same attacker - nothing else from them on this pool/token
+            // slips in between the front-run and this back-run.
+            let back_run = swaps.iter().find(|&&(idx, tx, ref swap)| {
+                idx > victim_idx
+                    && tx.to.to_lowercase() == pool
+                    && tx.from.to_lowercase() == attacker
+                    && swap.token_path.first().map(|t| t.to_lowercase()) == Some(token.clone())
+            });
+
+            if let Some(&(back_idx, _, ref back_swap)) = back_run {
+                patterns.push(MEVPattern::SandwichAttack {
+                    victim_tx: victim_tx.hash.clone(),
+                    profit_estimate: back_swap.amount_in as f64 * oracle_price_usd(&token),
+                    front_run_idx: Some(front_idx),
+                    victim_idx: Some(victim_idx),
+                    back_run_idx: Some(back_idx),
+                });
+            }
+        }
+
+        patterns
+    }
+
+    /// Find a single sender swapping the same token across two different
+    /// routers in opposite directions within the window - buying it on one
+    /// venue and selling it on another.
+    fn detect_block_arbitrage(swaps: &[(usize, &Transaction, DecodedSwap)]) -> Vec<MEVPattern> {
+        let mut patterns = Vec::new();
+
+        for &(i, tx_a, ref swap_a) in swaps {
+            let Some(token) = swap_a.token_path.last().map(|t| t.to_lowercase()) else {
+                continue;
+            };
+            let router_a = tx_a.to.to_lowercase();
+
+            for &(j, tx_b, ref swap_b) in swaps {
+                if j <= i
+                    || tx_b.from.to_lowercase() != tx_a.from.to_lowercase()
+                    || tx_b.to.to_lowercase() == router_a
+                    || swap_b.token_path.first().map(|t| t.to_lowercase()) != Some(token.clone())
+                {
+                    continue;
+                }
+
+                patterns.push(MEVPattern::Arbitrage {
+                    dex_a: router_a.clone(),
+                    dex_b: tx_b.to.to_lowercase(),
+                    token: token.clone(),
+                    profit_estimate: 0.0,
+                    leg_a_idx: Some(i),
+                    leg_b_idx: Some(j),
+                });
+                break;
+            }
+        }
+
+        patterns
+    }
+}
+
+/// An ordered buffer of transactions - by block number then transaction
+/// index once mined, or by mempool arrival order while pending - that
+/// windowed MEV conditions correlate across. Unlike `MEVDetector::detect_mev`,
+/// which correlates through Redis so it can see across batches, a window
+/// just holds however much context the caller has assembled in memory.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionWindow {
+    transactions: Vec<Transaction>,
+}
+
+impl TransactionWindow {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `transaction` to the end of the window, assuming the caller
+    /// already maintains ordering (mined block order, or mempool arrival).
+    pub fn push(&mut self, transaction: Transaction) {
+        self.transactions.push(transaction);
+    }
+
+    pub fn as_slice(&self) -> &[Transaction] {
+        &self.transactions
+    }
+
+    /// The index of `transaction` within the window, if present - `None`
+    /// means it hasn't been added yet and windowed conditions can't
+    /// correlate it against its neighbors.
+    pub fn index_of(&self, transaction: &Transaction) -> Option<usize> {
+        self.transactions.iter().position(|tx| tx.hash == transaction.hash)
+    }
+}
+
+/// Pure, stateless block/window-level MEV detection - the counterpart to
+/// `MEVDetector::detect_mev`'s single-transaction, Redis-correlated
+/// heuristics. Needs no detector instance (no Redis, no watchlist), so
+/// `RuleCondition::evaluate_in_context` can call it directly over whatever
+/// window the engine has assembled.
+pub fn detect_mev_window(txs: &[Transaction]) -> Vec<MEVPattern> {
+    let swaps: Vec<(usize, &Transaction, DecodedSwap)> = txs
+        .iter()
+        .enumerate()
+        .filter(|(_, tx)| MEVDetector::known_dex(&tx.to))
+        .filter_map(|(i, tx)| decode_v2_swap(tx).map(|swap| (i, tx, swap)))
+        .collect();
+
+    let mut patterns = MEVDetector::detect_block_sandwiches(&swaps);
+    patterns.extend(MEVDetector::detect_block_arbitrage(&swaps));
+    patterns.extend(detect_block_frontrun_backrun(txs));
+    patterns
+}
+
+/// Scans the whole window - not just decoded swaps - for generic
+/// front-run / back-run relationships keyed on contract + function
+/// selector, since these patterns don't require a decodable V2 swap the
+/// way sandwich and arbitrage detection do.
+fn detect_block_frontrun_backrun(txs: &[Transaction]) -> Vec<MEVPattern> {
+    let mut patterns = Vec::new();
+    for (target_idx, target) in txs.iter().enumerate() {
+        if let Some(pattern) = detect_frontrun(target_idx, target, txs) {
+            patterns.push(pattern);
+        }
+        if let Some(pattern) = detect_backrun(target_idx, target, txs) {
+            patterns.push(pattern);
+        }
+    }
+    patterns
+}
+
+/// A transaction earlier in the window, to the same contract + function
+/// selector as `target` but from a different sender, paying a strictly
+/// higher effective gas price - the shape of a front-run racing to land
+/// ahead of `target`.
+fn detect_frontrun(target_idx: usize, target: &Transaction, txs: &[Transaction]) -> Option<MEVPattern> {
+    let target_selector = function_selector(&target.data)?;
+    let target_gas = GasAnalysis::compute(target, base_fee_per_gas(target)).effective_gas_price_wei;
+
+    txs[..target_idx].iter().enumerate().find_map(|(idx, tx)| {
+        if tx.from.eq_ignore_ascii_case(&target.from) || !tx.to.eq_ignore_ascii_case(&target.to) {
+            return None;
+        }
+        if function_selector(&tx.data)? != target_selector {
+            return None;
+        }
+        let gas = GasAnalysis::compute(tx, base_fee_per_gas(tx)).effective_gas_price_wei;
+        if gas <= target_gas {
+            return None;
+        }
+        Some(MEVPattern::FrontRun {
+            attacker_idx: idx,
+            target_idx,
+            attacker_tx: tx.hash.clone(),
+            target_tx: target.hash.clone(),
+        })
+    })
+}
+
+/// The transaction placed directly after `target` in the window, to the
+/// same contract, at a gas price just under it - the shape of a back-run
+/// riding in right after a large swap without overpaying once it's
+/// guaranteed to land after.
+fn detect_backrun(target_idx: usize, target: &Transaction, txs: &[Transaction]) -> Option<MEVPattern> {
+    if !MEVDetector::known_dex(&target.to) {
+        return None;
+    }
+    let candidate = txs.get(target_idx + 1)?;
+    if candidate.from.eq_ignore_ascii_case(&target.from) || !candidate.to.eq_ignore_ascii_case(&target.to) {
+        return None;
+    }
+    let target_gas = GasAnalysis::compute(target, base_fee_per_gas(target)).effective_gas_price_wei;
+    let candidate_gas = GasAnalysis::compute(candidate, base_fee_per_gas(candidate)).effective_gas_price_wei;
+    // "Just below": strictly cheaper but within 10% of the target's price,
+    // not an unrelated low-fee transaction that happens to follow it.
+    if candidate_gas >= target_gas || candidate_gas < target_gas * 9 / 10 {
+        return None;
+    }
+    Some(MEVPattern::BackRun {
+        attacker_idx: target_idx + 1,
+        target_idx,
+        attacker_tx: candidate.hash.clone(),
+        target_tx: target.hash.clone(),
+    })
+}
+
+/// First 10 characters of `0x`-prefixed calldata (`0x` + 8 hex digits),
+/// i.e. the 4-byte function selector, matching the convention
+/// `RuleCondition::evaluate_contract_call` already uses.
+fn function_selector(data: &str) -> Option<&str> {
+    data.get(0..10)
+}
+
+/// A DEX-router call decoded from calldata: the token path it walks and the
+/// input amount, for the subset of Uniswap-V2-shaped swap functions
+/// `decode_v2_swap` recognizes.
+#[derive(Debug, Clone)]
+struct DecodedSwap {
+    token_path: Vec<String>,
+    amount_in: u128,
+}
+
+/// 4-byte selector (hex, no `0x`) -> number of leading static `uint256`
+/// words before the `address[] path` offset pointer, for the Uniswap-V2
+/// router functions whose calldata this crate decodes. ETH-in variants take
+/// the swapped-in amount from `msg.value` (the transaction's `value` field)
+/// instead of a calldata argument, hence one fewer leading word.
+const V2_SWAP_SELECTORS: &[(&str, usize)] = &[
+    ("38ed1739", 2), // swapExactTokensForTokens
+    ("8803dbee", 2), // swapTokensForExactTokens
+    ("7ff36ab5", 1), // swapExactETHForTokens
+    ("4a25d94a", 2), // swapTokensForExactETH
+    ("18cbafe5", 2), // swapExactTokensForETH
+    ("fb3bdb41", 1), // swapETHForExactTokens
+];
+
+/// Decode a V2-shaped router call's token path and input amount from
+/// calldata. Returns `None` for any call this crate doesn't recognize,
+/// including V3's single-hop and multi-hop-encoded-bytes calls.
+fn decode_v2_swap(transaction: &Transaction) -> Option<DecodedSwap> {
+    let bytes = decode_hex(&transaction.data)?;
+    if bytes.len() < 4 {
+        return None;
+    }
+    let selector = hex_string(&bytes[0..4]);
+    let leading_words = V2_SWAP_SELECTORS
+        .iter()
+        .find(|(s, _)| *s == selector)
+        .map(|(_, n)| *n)?;
+    let args = &bytes[4..];
+
+    let amount_in = if leading_words == 2 {
+        read_u256_as_u128(args, 0)?
+    } else {
+        transaction.value.parse::<u128>().unwrap_or(0)
+    };
+
+    let offset = read_u256_as_u128(args, leading_words * 32)? as usize;
+    let len = read_u256_as_u128(args, offset)? as usize;
+    let mut token_path = Vec::with_capacity(len);
+    for i in 0..len {
+        let word_start = offset + 32 + i * 32;
+        let word = args.get(word_start..word_start + 32)?;
+        token_path.push(format!("0x{}", hex_string(&word[12..32])));
+    }
+
+    Some(DecodedSwap {
+        token_path,
+        amount_in,
+    })
+}
+
+/// Reads a 32-byte big-endian word at `at` and truncates it to its low 16
+/// bytes. Token amounts and ABI offsets in practice fit comfortably inside a
+/// `u128`, so the (necessarily zero) high bytes are discarded rather than
+/// widening every caller to `U256`.
+fn read_u256_as_u128(data: &[u8], at: usize) -> Option<u128> {
+    let word = data.get(at..at + 32)?;
+    let mut buf = [0u8; 16];
+    buf.copy_from_slice(&word[16..32]);
+    Some(u128::from_be_bytes(buf))
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_hex(data: &str) -> Option<Vec<u8>> {
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    if data.len() % 2 != 0 {
+        return None;
+    }
+    (0..data.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&data[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// USD price for a token, used to turn an attacker's net token delta into a
+/// dollar profit estimate. Placeholder until a real price oracle is wired
+/// in: returns 0.0 for every token.
+fn oracle_price_usd(_token: &str) -> f64 {
+    0.0
 }
 
 #[derive(Debug, Clone)]
 pub enum MEVPattern {
     HighGasPrice {
-        gas_price_gwei: f64,
+        priority_tip_gwei: f64,
         threshold: f64,
     },
     DEXInteraction {
@@ -294,23 +1024,51 @@ pub enum MEVPattern {
     SandwichAttack {
         victim_tx: String,
         profit_estimate: f64,
+        front_run_idx: Option<usize>,
+        victim_idx: Option<usize>,
+        back_run_idx: Option<usize>,
     },
     Arbitrage {
         dex_a: String,
         dex_b: String,
         token: String,
         profit_estimate: f64,
+        leg_a_idx: Option<usize>,
+        leg_b_idx: Option<usize>,
+    },
+    WatchlistHit {
+        address: String,
+    },
+    /// An earlier transaction to the same contract + function selector as
+    /// `target_tx`, from a different sender, at a strictly higher
+    /// effective gas price - the shape of a front-run racing to land
+    /// before it.
+    FrontRun {
+        attacker_idx: usize,
+        target_idx: usize,
+        attacker_tx: String,
+        target_tx: String,
+    },
+    /// A transaction placed directly after `target_tx` to the same
+    /// contract, at a gas price just below it - the shape of a back-run
+    /// riding in right after a large swap.
+    BackRun {
+        attacker_idx: usize,
+        target_idx: usize,
+        attacker_tx: String,
+        target_tx: String,
     },
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::action_dispatcher::NoopActionDispatcher;
     use crate::rule_dsl::{RuleCondition, ComparisonOperator};
 
     #[tokio::test]
     async fn test_rule_execution() {
-        let executor = RuleExecutor::new(10);
+        let executor = RuleExecutor::new(10, Arc::new(NoopActionDispatcher::new()));
 
         let transaction = Transaction {
             hash: "0x123".to_string(),
@@ -350,7 +1108,13 @@ mod tests {
             updated_at: Utc::now(),
         };
 
-        let alerts = executor.execute_rules(&transaction, &[rule]).await.unwrap();
+        let mut window = TransactionWindow::new();
+        window.push(transaction.clone());
+
+        let (alerts, _outcomes) = executor
+            .execute_rules(&transaction, &[rule], &window)
+            .await
+            .unwrap();
         assert_eq!(alerts.len(), 1);
         assert_eq!(alerts[0].severity, AlertSeverity::Medium);
         assert!(alerts[0].description.contains("0x123"));
@@ -359,7 +1123,7 @@ mod tests {
 
     #[tokio::test]
     async fn test_risk_scoring() {
-        let scorer = RiskScorer::new();
+        let scorer = RiskScorer::new(Arc::new(InMemoryAddressIntel::default()), Watchlist::new());
 
         let transaction = Transaction {
             hash: "0x123".to_string(),
@@ -382,9 +1146,254 @@ mod tests {
         assert!(score > 0.5); // Should be high risk due to high value and gas price
     }
 
+    #[tokio::test]
+    async fn hex_encoded_value_still_scores_as_high_value() {
+        let scorer = RiskScorer::new(Arc::new(InMemoryAddressIntel::default()), Watchlist::new());
+
+        let mut transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            value: "0x56bc75e2d63100000".to_string(), // 100 ETH, hex-encoded
+            gas: "21000".to_string(),
+            gas_price: "1".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        };
+
+        let hex_score = scorer.calculate_risk_score(&transaction).await.unwrap();
+        transaction.value = "100000000000000000000".to_string(); // same amount, decimal
+        let decimal_score = scorer.calculate_risk_score(&transaction).await.unwrap();
+
+        assert_eq!(hex_score, decimal_score);
+    }
+
+    #[tokio::test]
+    async fn value_above_u128_max_still_templates() {
+        let executor = RuleExecutor::new(10, Arc::new(NoopActionDispatcher::new()));
+
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            // One above u128::MAX, which `.parse::<u128>()` would reject.
+            value: "340282366920938463463374607431768211456".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "1".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        };
+
+        let rule = Rule {
+            id: Uuid::new_v4(),
+            name: "Test Rule".to_string(),
+            description: "Test rule description".to_string(),
+            conditions: vec![RuleCondition::PatternMatch {
+                field: "hash".to_string(),
+                pattern: "0x123".to_string(),
+                regex: false,
+            }],
+            actions: vec![RuleAction::CreateAlert {
+                severity: AlertSeverity::Medium,
+                title: "High Value Transaction".to_string(),
+                description: "Transaction {{hash}} has value: {{value_eth}} ETH".to_string(),
+                tags: vec!["high-value".to_string()],
+                metadata: serde_json::json!({}),
+            }],
+            enabled: true,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+
+        let mut window = TransactionWindow::new();
+        window.push(transaction.clone());
+
+        let (alerts, _outcomes) = executor
+            .execute_rules(&transaction, &[rule], &window)
+            .await
+            .unwrap();
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].description.contains("340282366920938463463.374607"));
+    }
+
+    #[tokio::test]
+    async fn eip3607_contract_sender_raises_risk_score() {
+        let address_intel = InMemoryAddressIntel::new(
+            vec!["0xabc".to_string()],
+            Vec::new(),
+            Vec::new(),
+        );
+        let scorer = RiskScorer::new(Arc::new(address_intel), Watchlist::new());
+
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xABC".to_string(), // case-insensitive match against the seeded set
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "1".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        };
+
+        let score = scorer.calculate_risk_score(&transaction).await.unwrap();
+        assert!(score >= 0.5);
+    }
+
+    #[tokio::test]
+    async fn sanctioned_and_malicious_addresses_are_suspicious() {
+        let address_intel = InMemoryAddressIntel::new(
+            Vec::new(),
+            vec!["0xsanctioned".to_string()],
+            vec!["0xmalicious".to_string()],
+        );
+
+        assert!(address_intel.is_sanctioned("0xSanctioned").await.unwrap());
+        assert!(address_intel.is_known_malicious("0xMalicious").await.unwrap());
+        assert!(!address_intel.is_sanctioned("0xclean").await.unwrap());
+    }
+
+    #[test]
+    fn gas_analysis_uses_legacy_gas_price_for_type_0() {
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "50000000000".to_string(), // 50 Gwei
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        };
+
+        let analysis = GasAnalysis::compute(&transaction, 10_000_000_000); // 10 Gwei base fee
+        assert_eq!(analysis.kind, TxKind::Legacy);
+        assert_eq!(analysis.effective_gas_price_gwei(), 50.0);
+        // Legacy transactions pay their whole gas price; the part above the
+        // 10 Gwei base fee counts as tip.
+        assert_eq!(analysis.priority_tip_gwei(), 40.0);
+    }
+
+    #[test]
+    fn gas_analysis_computes_effective_price_for_type_2() {
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "0".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({
+                "type": "0x2",
+                "maxFeePerGas": "100000000000", // 100 Gwei
+                "maxPriorityFeePerGas": "3000000000", // 3 Gwei
+            }),
+        };
+
+        // Base fee is 80 Gwei, so max fee leaves 20 Gwei of headroom - more
+        // than the 3 Gwei priority fee, so the full tip is paid.
+        let analysis = GasAnalysis::compute(&transaction, 80_000_000_000);
+        assert_eq!(analysis.kind, TxKind::DynamicFee);
+        assert_eq!(analysis.effective_gas_price_gwei(), 83.0);
+        assert_eq!(analysis.priority_tip_gwei(), 3.0);
+    }
+
+    #[test]
+    fn gas_analysis_computes_effective_price_for_type_3_blob() {
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "0".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({
+                "type": "0x3",
+                "maxFeePerGas": "100000000000", // 100 Gwei
+                "maxPriorityFeePerGas": "3000000000", // 3 Gwei
+            }),
+        };
+
+        // Blob transactions price the same way type-2 does - confirm they
+        // don't fall through to the legacy `gas_price` (0 here) branch.
+        let analysis = GasAnalysis::compute(&transaction, 80_000_000_000);
+        assert_eq!(analysis.kind, TxKind::Blob);
+        assert_eq!(analysis.effective_gas_price_gwei(), 83.0);
+        assert_eq!(analysis.priority_tip_gwei(), 3.0);
+    }
+
+    #[test]
+    fn gas_analysis_caps_tip_at_available_headroom() {
+        let transaction = Transaction {
+            hash: "0x123".to_string(),
+            chain_id: 1,
+            from: "0xabc".to_string(),
+            to: "0xdef".to_string(),
+            value: "0".to_string(),
+            gas: "21000".to_string(),
+            gas_price: "0".to_string(),
+            data: "0x".to_string(),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: None,
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({
+                "type": "0x2",
+                "maxFeePerGas": "100000000000", // 100 Gwei
+                "maxPriorityFeePerGas": "50000000000", // 50 Gwei
+            }),
+        };
+
+        // Base fee is 90 Gwei, leaving only 10 Gwei of headroom below the
+        // max fee even though 50 Gwei of priority fee was bid.
+        let analysis = GasAnalysis::compute(&transaction, 90_000_000_000);
+        assert_eq!(analysis.effective_gas_price_gwei(), 100.0);
+        assert_eq!(analysis.priority_tip_gwei(), 10.0);
+    }
+
     #[tokio::test]
     async fn test_mev_detection() {
-        let detector = MEVDetector::new();
+        let correlation_store = Arc::new(crate::redis_pool::LocalCorrelationStore::new());
+        let detector = MEVDetector::new(correlation_store, Duration::from_secs(300), Watchlist::new());
 
         let transaction = Transaction {
             hash: "0x123".to_string(),
@@ -406,4 +1415,114 @@ mod tests {
         let patterns = detector.detect_mev(&transaction).await.unwrap();
         assert!(patterns.len() >= 2); // Should detect high gas price and DEX interaction
     }
+
+    fn word_u256(v: u128) -> String {
+        format!("{:064x}", v)
+    }
+
+    fn word_address(addr40: &str) -> String {
+        format!("{:0>64}", addr40)
+    }
+
+    /// Builds calldata for `swapExactTokensForTokens(amountIn, amountOutMin,
+    /// path, to, deadline)`, the only shape these tests need to exercise
+    /// `decode_v2_swap`'s offset/length decoding.
+    fn build_swap_calldata(amount_in: u128, path: &[&str]) -> String {
+        let mut data = String::from("38ed1739");
+        data += &word_u256(amount_in); // amountIn
+        data += &word_u256(0); // amountOutMin
+        data += &word_u256(160); // offset to path, past 5 static words
+        data += &word_address(&"f".repeat(40)); // to
+        data += &word_u256(9_999_999_999); // deadline
+        data += &word_u256(path.len() as u128); // path length
+        for addr in path {
+            data += &word_address(addr);
+        }
+        format!("0x{}", data)
+    }
+
+    fn swap_transaction(hash: &str, from: &str, to: &str, amount_in: u128, path: &[&str]) -> Transaction {
+        Transaction {
+            hash: hash.to_string(),
+            chain_id: 1,
+            from: from.to_string(),
+            to: to.to_string(),
+            value: "0".to_string(),
+            gas: "200000".to_string(),
+            gas_price: "20000000000".to_string(),
+            data: build_swap_calldata(amount_in, path),
+            nonce: "1".to_string(),
+            timestamp: 1640995200,
+            block_number: Some(100),
+            transaction_index: None,
+            status: "pending".to_string(),
+            raw: serde_json::json!({}),
+        }
+    }
+
+    #[test]
+    fn detect_mev_block_finds_sandwich_and_arbitrage() {
+        let router_a = "0x7a250d5630b4cf539739df2c5dacb4c659f2488d"; // Uniswap V2 Router
+        let router_b = "0xd9e1ce17f2641f24ae83637ab66a2cca9c378b9f"; // SushiSwap Router
+        let token_a = "a".repeat(40);
+        let token_b = "b".repeat(40);
+
+        let front_run = swap_transaction(
+            "0xfront",
+            "0xattacker",
+            router_a,
+            1_000_000_000_000_000_000,
+            &[&token_a, &token_b],
+        );
+        let victim = swap_transaction(
+            "0xvictim",
+            "0xvictim_sender",
+            router_a,
+            500_000_000_000_000_000,
+            &[&token_a, &token_b],
+        );
+        let back_run = swap_transaction(
+            "0xback",
+            "0xattacker",
+            router_a,
+            2_000_000_000_000_000_000,
+            &[&token_b, &token_a],
+        );
+        let arb_leg_a = swap_transaction(
+            "0xarb_a",
+            "0xarb_trader",
+            router_a,
+            1_000_000_000_000_000_000,
+            &[&token_a, &token_b],
+        );
+        let arb_leg_b = swap_transaction(
+            "0xarb_b",
+            "0xarb_trader",
+            router_b,
+            1_000_000_000_000_000_000,
+            &[&token_b, &token_a],
+        );
+
+        let correlation_store = Arc::new(crate::redis_pool::LocalCorrelationStore::new());
+        let detector = MEVDetector::new(correlation_store, Duration::from_secs(300), Watchlist::new());
+
+        let patterns = detector.detect_mev_block(&[
+            front_run,
+            victim,
+            back_run,
+            arb_leg_a,
+            arb_leg_b,
+        ]);
+
+        assert!(patterns.iter().any(
+            |p| matches!(p, MEVPattern::SandwichAttack { victim_tx, .. } if victim_tx == "0xvictim")
+        ));
+        assert!(patterns.iter().any(|p| matches!(
+            p,
+            MEVPattern::Arbitrage { dex_a, dex_b, token, .. }
+                if dex_a == &router_a.to_lowercase()
+                    && dex_b == &router_b.to_lowercase()
+                    && token == &token_b.to_lowercase()
+        )));
+    }
 }
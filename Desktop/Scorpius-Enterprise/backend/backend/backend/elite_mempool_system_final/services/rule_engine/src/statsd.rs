@@ -0,0 +1,183 @@
+//! StatsD UDP push export, gated behind the `statsd` feature. Like
+//! `otel_export`, this re-emits the existing `prometheus::Registry` on a
+//! timer rather than introducing a second, independently-maintained set of
+//! instruments, so the Prometheus `/metrics` scrape and the StatsD push stay
+//! in agreement.
+
+use anyhow::{Context, Result};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+use crate::metrics::Metrics;
+
+/// Configuration for the StatsD push exporter.
+#[derive(Debug, Clone)]
+pub struct StatsdConfig {
+    pub server_addr: SocketAddr,
+    pub push_interval: Duration,
+    /// Metric name prefix, e.g. `"scorpius.rule_engine"`.
+    pub prefix: String,
+    /// Datagrams are flushed as soon as a new line would push the buffer
+    /// past this size, so a single push never exceeds a conservative UDP
+    /// payload size.
+    pub max_datagram_bytes: usize,
+}
+
+impl Default for StatsdConfig {
+    fn default() -> Self {
+        Self {
+            server_addr: ([127, 0, 0, 1], 8125).into(),
+            push_interval: Duration::from_secs(10),
+            prefix: "scorpius.rule_engine".to_string(),
+            max_datagram_bytes: 1024,
+        }
+    }
+}
+
+/// Periodically gathers the Prometheus registry and re-emits it as StatsD
+/// gauges over UDP, batching metric lines into datagrams and flushing on an
+/// interval rather than sending one packet per metric.
+pub struct StatsdExporter {
+    config: StatsdConfig,
+    metrics: Arc<Metrics>,
+}
+
+impl StatsdExporter {
+    pub fn new(config: StatsdConfig, metrics: Arc<Metrics>) -> Self {
+        Self { config, metrics }
+    }
+
+    /// Bind a UDP socket and start the background push loop.
+    pub async fn start(self) -> Result<()> {
+        let socket = UdpSocket::bind(("0.0.0.0", 0))
+            .await
+            .context("failed to bind StatsD UDP socket")?;
+        socket
+            .connect(self.config.server_addr)
+            .await
+            .context("failed to connect StatsD UDP socket")?;
+
+        let config = self.config;
+        let metrics = self.metrics;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.push_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = push_once(&socket, &config, &metrics).await {
+                    log::warn!("StatsD export tick failed: {}", e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+async fn push_once(socket: &UdpSocket, config: &StatsdConfig, metrics: &Arc<Metrics>) -> Result<()> {
+    let mut batch = DatagramBatch::new(socket, config.max_datagram_bytes);
+
+    for family in metrics.registry().gather() {
+        let name = family.get_name();
+
+        for metric in family.get_metric() {
+            let labels: String = metric
+                .get_label()
+                .iter()
+                .map(|l| format!(".{}", sanitize(l.get_value())))
+                .collect();
+
+            if metric.has_counter() {
+                let line = format!(
+                    "{}.{}{}:{}|g",
+                    config.prefix,
+                    name,
+                    labels,
+                    metric.get_counter().get_value()
+                );
+                batch.push(&line).await?;
+            } else if metric.has_gauge() {
+                let line = format!(
+                    "{}.{}{}:{}|g",
+                    config.prefix,
+                    name,
+                    labels,
+                    metric.get_gauge().get_value()
+                );
+                batch.push(&line).await?;
+            } else if metric.has_histogram() {
+                let histogram = metric.get_histogram();
+                batch
+                    .push(&format!(
+                        "{}.{}{}.sum:{}|g",
+                        config.prefix,
+                        name,
+                        labels,
+                        histogram.get_sample_sum()
+                    ))
+                    .await?;
+                batch
+                    .push(&format!(
+                        "{}.{}{}.count:{}|g",
+                        config.prefix,
+                        name,
+                        labels,
+                        histogram.get_sample_count()
+                    ))
+                    .await?;
+            }
+        }
+    }
+
+    batch.flush().await
+}
+
+/// A sanitized metric name segment is safe to drop straight into a StatsD
+/// line without it being mistaken for a delimiter.
+fn sanitize(value: &str) -> String {
+    value.replace([':', '|', '@', '\n'], "_")
+}
+
+/// Accumulates StatsD lines (newline-separated, per the multi-metric packet
+/// convention most StatsD servers accept) and flushes as a single UDP
+/// datagram once the configured size limit would otherwise be exceeded.
+struct DatagramBatch<'a> {
+    socket: &'a UdpSocket,
+    max_bytes: usize,
+    buffer: String,
+}
+
+impl<'a> DatagramBatch<'a> {
+    fn new(socket: &'a UdpSocket, max_bytes: usize) -> Self {
+        Self {
+            socket,
+            max_bytes,
+            buffer: String::new(),
+        }
+    }
+
+    async fn push(&mut self, line: &str) -> Result<()> {
+        if !self.buffer.is_empty() && self.buffer.len() + 1 + line.len() > self.max_bytes {
+            self.flush().await?;
+        }
+        if !self.buffer.is_empty() {
+            self.buffer.push('\n');
+        }
+        self.buffer.push_str(line);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        self.socket
+            .send(self.buffer.as_bytes())
+            .await
+            .context("failed to send StatsD datagram")?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+//! Generic supervision for long-running background tasks (metrics
+//! collection, rule reloading, ...): restarts a task with exponential
+//! backoff if it panics or returns before shutdown is signaled, instead of
+//! leaving the service silently degraded with a dead background task.
+
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Spawn `make_task` under supervision. Each call to `make_task` produces a
+/// fresh future to run; if that future returns or panics while
+/// `shutdown_rx` hasn't fired, `name` is logged and the task is respawned
+/// after an exponential backoff (capped at `MAX_BACKOFF`, reset once a run
+/// survives at least that long). Returns the supervisor's own `JoinHandle`
+/// so callers can join it (with a timeout) during shutdown.
+pub fn spawn_supervised<F, Fut>(
+    name: &'static str,
+    mut shutdown_rx: watch::Receiver<bool>,
+    mut make_task: F,
+) -> JoinHandle<()>
+where
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: std::future::Future<Output = ()> + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            if *shutdown_rx.borrow() {
+                return;
+            }
+
+            let started = tokio::time::Instant::now();
+            let result = tokio::spawn(make_task()).await;
+
+            if *shutdown_rx.borrow() {
+                return;
+            }
+
+            match result {
+                Ok(()) => log::warn!("background task '{}' exited; restarting", name),
+                Err(e) => log::error!("background task '{}' panicked: {}; restarting", name, e),
+            }
+
+            // A task that ran for a while before failing isn't flapping -
+            // don't penalize it with the full accumulated backoff.
+            if started.elapsed() >= MAX_BACKOFF {
+                backoff = INITIAL_BACKOFF;
+            }
+
+            tokio::select! {
+                _ = tokio::time::sleep(backoff) => {}
+                _ = shutdown_rx.changed() => {
+                    if *shutdown_rx.borrow() {
+                        return;
+                    }
+                }
+            }
+            backoff = (backoff * 2).min(MAX_BACKOFF);
+        }
+    })
+}
@@ -0,0 +1,246 @@
+//! Multi-source gas price oracle. A single RPC `get_gas_price()` call is
+//! noisy and easily gamed, so `GasOracle` queries several independent
+//! sources - the node's own EIP-1559 projection, an `eth_feeHistory`
+//! percentile estimator, and any configured HTTP gas APIs - and folds
+//! them into one per-gas price with a median or weighted median. Quotes
+//! are cached per tier for a short TTL so repeated path evaluations in
+//! `dfs_explore_sequential` don't re-hit every source on every call.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use ethers::types::{BlockNumber, U256};
+use eyre::Result;
+use lazy_static::lazy_static;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use crate::{AppConfig, Client};
+
+/// Congestion tier a gas quote targets, mirroring the tiers most gas
+/// APIs (and `eth_feeHistory` reward percentiles) expose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GasPriceTier {
+    Low,
+    Standard,
+    Fast,
+}
+
+impl GasPriceTier {
+    fn fee_history_percentile(self) -> f64 {
+        match self {
+            GasPriceTier::Low => 25.0,
+            GasPriceTier::Standard => 50.0,
+            GasPriceTier::Fast => 90.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GasOracleMode {
+    Median,
+    WeightedMedian,
+}
+
+/// One independent source of gas price quotes. `Node` reuses the node's
+/// own EIP-1559 base-fee + priority-fee projection and ignores tier;
+/// `FeeHistory` and `HttpApi` both quote per-tier.
+#[derive(Debug, Clone)]
+pub enum GasSourceConfig {
+    Node,
+    FeeHistory,
+    HttpApi { url: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct GasOracleConfig {
+    pub mode: GasOracleMode,
+    pub tier: GasPriceTier,
+    pub sources: Vec<(GasSourceConfig, f64)>,
+    pub cache_ttl: Duration,
+}
+
+lazy_static! {
+    static ref GAS_PRICE_CACHE: Arc<Mutex<HashMap<GasPriceTier, (U256, Instant)>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// Projects the base fee one block ahead of `parent` using the EIP-1559
+/// recurrence, so the node source prices the block the arb would
+/// actually land in rather than the block that was just mined. This is
+/// the only implementation of the recurrence in the crate - `main.rs`
+/// only holds the `base_fee_change_denominator`/`elasticity_multiplier`
+/// config values it's parameterized by, not a copy of the formula.
+fn project_next_base_fee(
+    base_fee_parent: U256,
+    gas_used: U256,
+    gas_limit: U256,
+    elasticity_multiplier: u64,
+    base_fee_change_denominator: u64,
+) -> U256 {
+    let gas_target = gas_limit / U256::from(elasticity_multiplier);
+    if gas_target.is_zero() || gas_used == gas_target {
+        return base_fee_parent;
+    }
+    if gas_used > gas_target {
+        let gas_used_delta = gas_used - gas_target;
+        let base_fee_delta = std::cmp::max(
+            base_fee_parent * gas_used_delta / gas_target / U256::from(base_fee_change_denominator),
+            U256::one(),
+        );
+        base_fee_parent + base_fee_delta
+    } else {
+        let gas_used_delta = gas_target - gas_used;
+        let base_fee_delta = base_fee_parent * gas_used_delta / gas_target / U256::from(base_fee_change_denominator);
+        base_fee_parent.saturating_sub(base_fee_delta)
+    }
+}
+
+/// Node source: EIP-1559 base-fee + priority-fee projection, falling
+/// back to the legacy `get_gas_price()` path on pre-London chains.
+async fn quote_from_node(client: &Client, config: &AppConfig) -> Result<U256> {
+    let latest_block = client
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| eyre::eyre!("node returned no latest block"))?;
+
+    let base_fee_parent = match latest_block.base_fee_per_gas {
+        Some(base_fee_parent) => base_fee_parent,
+        None => return Ok(client.get_gas_price().await?),
+    };
+
+    let base_fee_next = project_next_base_fee(
+        base_fee_parent,
+        latest_block.gas_used,
+        latest_block.gas_limit,
+        config.elasticity_multiplier,
+        config.base_fee_change_denominator,
+    );
+    let priority_fee_wei: U256 = crate::parse_units(config.max_priority_fee_per_gas_gwei, 9)?.into();
+    Ok(base_fee_next + priority_fee_wei)
+}
+
+/// `eth_feeHistory`-based estimator: the next base fee plus the mean
+/// reward paid at `tier`'s percentile over the trailing window.
+async fn quote_from_fee_history(client: &Client, tier: GasPriceTier) -> Result<U256> {
+    let history = client
+        .fee_history(10u64, BlockNumber::Latest, &[tier.fee_history_percentile()])
+        .await?;
+    let base_fee_next = history
+        .base_fee_per_gas
+        .last()
+        .copied()
+        .ok_or_else(|| eyre::eyre!("eth_feeHistory returned no base fees"))?;
+    let rewards: Vec<U256> = history.reward.iter().filter_map(|block_rewards| block_rewards.first().copied()).collect();
+    if rewards.is_empty() {
+        return Ok(base_fee_next);
+    }
+    let reward_sum = rewards.iter().fold(U256::zero(), |acc, r| acc + r);
+    let mean_reward = reward_sum / U256::from(rewards.len());
+    Ok(base_fee_next + mean_reward)
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct HttpGasApiResponse {
+    low: f64,
+    standard: f64,
+    fast: f64,
+}
+
+/// Configurable HTTP gas API returning `{low, standard, fast}` gwei
+/// prices (the common shape for hosted gas-station style APIs).
+async fn quote_from_http_api(url: &str, tier: GasPriceTier) -> Result<U256> {
+    let response: HttpGasApiResponse = reqwest::get(url).await?.json().await?;
+    let gwei = match tier {
+        GasPriceTier::Low => response.low,
+        GasPriceTier::Standard => response.standard,
+        GasPriceTier::Fast => response.fast,
+    };
+    Ok(crate::parse_units(gwei, 9)?.into())
+}
+
+fn wei_to_gwei(wei: U256) -> f64 {
+    wei.to_string().parse::<f64>().unwrap_or(f64::NAN) / 1e9
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let n = values.len();
+    if n == 0 {
+        return 0.0;
+    }
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+/// Sorts `(price, weight)` pairs by price and walks cumulative weight
+/// until it reaches half of the total weight sum, returning that price.
+fn weighted_median(mut pairs: Vec<(f64, f64)>) -> f64 {
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let total_weight: f64 = pairs.iter().map(|(_, weight)| weight).sum();
+    if total_weight <= 0.0 {
+        return pairs.last().map(|(price, _)| *price).unwrap_or(0.0);
+    }
+    let half = total_weight / 2.0;
+    let mut cumulative = 0.0;
+    for (price, weight) in &pairs {
+        cumulative += weight;
+        if cumulative >= half {
+            return *price;
+        }
+    }
+    pairs.last().map(|(price, _)| *price).unwrap_or(0.0)
+}
+
+pub struct GasOracle {
+    config: GasOracleConfig,
+}
+
+impl GasOracle {
+    pub fn new(config: GasOracleConfig) -> Self {
+        Self { config }
+    }
+
+    /// Returns the current per-gas price in wei for `self.config.tier`,
+    /// querying every configured source and aggregating per
+    /// `self.config.mode` on a cache miss.
+    pub async fn quote(&self, client: &Client, app_config: &AppConfig) -> Result<U256> {
+        {
+            let cache = GAS_PRICE_CACHE.lock().await;
+            if let Some((price, fetched_at)) = cache.get(&self.config.tier) {
+                if fetched_at.elapsed() < self.config.cache_ttl {
+                    return Ok(*price);
+                }
+            }
+        }
+
+        let mut quotes: Vec<(f64, f64)> = Vec::with_capacity(self.config.sources.len());
+        for (source, weight) in &self.config.sources {
+            let quote_wei = match source {
+                GasSourceConfig::Node => quote_from_node(client, app_config).await,
+                GasSourceConfig::FeeHistory => quote_from_fee_history(client, self.config.tier).await,
+                GasSourceConfig::HttpApi { url } => quote_from_http_api(url, self.config.tier).await,
+            };
+            match quote_wei {
+                Ok(wei) => quotes.push((wei_to_gwei(wei), *weight)),
+                Err(e) => warn!("Gas oracle source {:?} failed: {}", source, e),
+            }
+        }
+
+        if quotes.is_empty() {
+            return Err(eyre::eyre!("all gas oracle sources failed"));
+        }
+
+        let aggregated_gwei = match self.config.mode {
+            GasOracleMode::Median => median(quotes.into_iter().map(|(price, _)| price).collect()),
+            GasOracleMode::WeightedMedian => weighted_median(quotes),
+        };
+        let price_wei: U256 = crate::parse_units(aggregated_gwei, 9)?.into();
+        GAS_PRICE_CACHE.lock().await.insert(self.config.tier, (price_wei, Instant::now()));
+        Ok(price_wei)
+    }
+}
@@ -8,29 +8,37 @@ extern crate log;
 
 use ethers::contract::abigen;
 use ethers::prelude::*;
-use ethers::providers::{Http, Provider};
-use ethers::types::{Address, U256};
+use ethers::providers::Provider;
+use ethers::types::{Address, I256, U256};
 
 use clap::Parser;
 use dotenvy::dotenv;
 use eyre::Result;
 use futures::future::join_all;
+use futures::StreamExt;
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::future::Future;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 
+mod gas_oracle;
+mod numeric;
+mod sim;
+mod transport;
+
 // -------------------
 // Type aliases & ABI bindings
 // -------------------
 
-// Shared provider type alias.
-type Client = Arc<Provider<Http>>;
+// Shared provider type alias, generic over whichever transport (HTTP,
+// WebSocket, IPC) `transport::ClientTransport` was connected with.
+pub(crate) type Client = Arc<Provider<transport::ClientTransport>>;
 
 // Generate contract bindings using abigen!  
 abigen!(
@@ -67,9 +75,41 @@ pub struct DexConfig {
     pub preferred_fees: Option<Vec<u32>>,
 }
 
+/// Which `SwapSimulator` backend `simulate_swap_step` quotes hops with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SimulatorKind {
+    /// One `eth_call` per fee tier per hop - simple, but makes the DFS
+    /// network-bound at any real search depth.
+    #[default]
+    Rpc,
+    /// Quotes against a local `revm` executor seeded from state read at
+    /// the scan's pinned block. Each hop builds its own simulator, so that
+    /// state is fetched again for every hop and not shared across hops.
+    Evm,
+}
+
+/// Which strategy `find_arbitrage_paths` uses to locate arbitrage
+/// opportunities within one scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathfinderKind {
+    /// Exhaustive token/dex DFS up to `max_hops` - simple, but scales
+    /// exponentially and misses profitable cycles longer than `max_hops`.
+    #[default]
+    Dfs,
+    /// Models the market as a directed graph of marginal exchange rates
+    /// and locates negative-weight cycles (i.e. a product of rates > 1)
+    /// with Bellman-Ford, then re-simulates surviving cycles at the real
+    /// loan size to price in slippage.
+    BellmanFord,
+}
+
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub rpc_url: String,
+    /// WebSocket endpoint used when `--watch` is set; falls back to
+    /// `rpc_url` if absent (e.g. an IPC path, or an RPC that's already
+    /// `ws://`/`wss://`).
+    pub ws_url: Option<String>,
     pub min_profit_usd: f64,
     pub max_hops: u8,
     pub scan_loan_amount_usd: f64,
@@ -81,6 +121,18 @@ pub struct AppConfig {
     pub slippage_tolerance: f64,
     pub chainlink_feeds: Option<HashMap<String, ChainlinkFeedConfig>>,
     pub fallback_eth_price_usd: f64,
+    /// Max age, in seconds, a Chainlink round's `updatedAt` may have
+    /// before it's rejected as stale.
+    pub max_feed_staleness_secs: u64,
+    pub simulator_kind: SimulatorKind,
+    pub pathfinder: PathfinderKind,
+    /// Tip offered on top of the projected base fee, in gwei.
+    pub max_priority_fee_per_gas_gwei: f64,
+    /// EIP-1559 `BASE_FEE_MAX_CHANGE_DENOMINATOR` (spec default 8).
+    pub base_fee_change_denominator: u64,
+    /// EIP-1559 `ELASTICITY_MULTIPLIER` (spec default 2).
+    pub elasticity_multiplier: u64,
+    pub gas_oracle: gas_oracle::GasOracleConfig,
 }
 
 #[derive(Debug, Clone)]
@@ -88,11 +140,12 @@ pub struct ChainlinkFeedConfig {
     pub address: Address,
 }
 
-/// FeedInfo now uses Provider<Http> to match the instance produced by IChainlinkAggregatorV3::new.
-/// Derive Clone so that we can clone FeedInfo.
+/// `FeedInfo` is generic over the same `Client` provider as everything
+/// else, so a Chainlink feed can be read over HTTP, WS, or IPC like any
+/// other contract binding. Derives `Clone` so it can live in `FEED_INFO_CACHE`.
 #[derive(Debug, Clone)]
 pub struct FeedInfo {
-    pub contract: IChainlinkAggregatorV3<Provider<Http>>,
+    pub contract: IChainlinkAggregatorV3<Provider<transport::ClientTransport>>,
     pub decimals: u8,
     pub address: Address,
 }
@@ -118,6 +171,12 @@ pub struct CliArgs {
 
     #[arg(long)]
     pub min_profit_usd_override: Option<f64>,
+
+    /// Run as a long-lived daemon: connect over WebSocket, subscribe to
+    /// new block headers, and re-scan on every block instead of exiting
+    /// after one pass.
+    #[arg(long)]
+    pub watch: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -227,58 +286,289 @@ pub mod u256_string_serialization {
 // Configuration Loader (Updated)
 // -------------------
 
-fn load_app_config() -> Result<AppConfig> {
-    dotenv().ok(); 
-    let rpc_url = env::var("RPC_URL").expect("RPC_URL must be set");
-
-    // Temporarily use just two tokens that are known to have liquidity.
-    let mut tokens_map = HashMap::new();
-    tokens_map.insert("WETH".to_string(), TokenConfig {
-        address: "0x4200000000000000000000000000000000000006".parse().unwrap(),
-        decimals: 18,
-    });
-    tokens_map.insert("USDC".to_string(), TokenConfig {
-        address: "0xd9aa3fC9B706A1f5a0dA5991E6571923F5287b2A".parse().unwrap(),
-        decimals: 6,
-    });
-
-    let mut dex_map = HashMap::new();
-
-    // ✅ UniswapV2 using a known standard V2 factory address.
-    dex_map.insert("UniswapV2".to_string(), DexConfig {
-        r#type: "V2".into(),
-        factory: Some("0x327Df1E6de05895d2ab08513aaDD9313Fe505d86".parse().unwrap()),
-        router: None,
-        quoter: None,
-        preferred_fees: None,
-    });
-
-    // ✅ UniswapV3 on Base using the confirmed addresses.
-    dex_map.insert("UniswapV3".to_string(), DexConfig {
-        r#type: "V3".into(),
-        factory: Some("0x327Ee6dd6f6a25a9A0D3bA5038366BC10c17496E".parse().unwrap()),
-        router: None,
-        quoter: Some("0x23d7efCE1f800DE3e36c1B6D4171068F5FF75EbB".parse().unwrap()), // Base Quoter V2
-        preferred_fees: Some(vec![500, 3000]),
-    });
-
-    // Set the base token symbol to one that is in the tokens map.
+/// Path to the multi-chain registry file, overridable so deployments can
+/// point at a mounted config without a rebuild.
+const DEFAULT_CHAIN_CONFIG_PATH: &str = "config/chains.toml";
+
+/// One `[[chains]]` entry as it appears in the TOML/JSON registry file -
+/// the on-disk shape, before it's validated and folded into `AppConfig`.
+#[derive(Debug, Deserialize)]
+struct ChainFileEntry {
+    chain_id: u64,
+    /// Human-friendly identifier matched against `--chain` (e.g. "base",
+    /// "arbitrum"); `--chain` also matches on `chain_id` itself, so an
+    /// entry without a name is still selectable.
+    name: Option<String>,
+    rpc_url: String,
+    #[serde(default)]
+    ws_url: Option<String>,
+    min_profit_usd: f64,
+    max_hops: u8,
+    scan_loan_amount_usd: f64,
+    base_token_symbol: String,
+    flashloan_fee_rate: f64,
+    slippage_tolerance: f64,
+    fallback_eth_price_usd: f64,
+    tokens: Vec<TokenFileEntry>,
+    dexes: Vec<DexFileEntry>,
+    #[serde(default)]
+    chainlink_feeds: Vec<ChainlinkFeedFileEntry>,
+    #[serde(default = "default_max_priority_fee_per_gas_gwei")]
+    max_priority_fee_per_gas_gwei: f64,
+    #[serde(default = "default_base_fee_change_denominator")]
+    base_fee_change_denominator: u64,
+    #[serde(default = "default_elasticity_multiplier")]
+    elasticity_multiplier: u64,
+    #[serde(default)]
+    gas_oracle: GasOracleFileEntry,
+    #[serde(default = "default_max_feed_staleness_secs")]
+    max_feed_staleness_secs: u64,
+}
+
+fn default_max_feed_staleness_secs() -> u64 {
+    3600
+}
+
+fn default_max_priority_fee_per_gas_gwei() -> f64 {
+    1.5
+}
+
+fn default_base_fee_change_denominator() -> u64 {
+    8
+}
+
+fn default_elasticity_multiplier() -> u64 {
+    2
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenFileEntry {
+    symbol: String,
+    address: Address,
+    decimals: u8,
+}
+
+#[derive(Debug, Deserialize)]
+struct DexFileEntry {
+    name: String,
+    #[serde(rename = "type")]
+    r#type: String,
+    factory: Option<Address>,
+    router: Option<Address>,
+    quoter: Option<Address>,
+    preferred_fees: Option<Vec<u32>>,
+}
+
+/// On-disk shape of `[chains.gas_oracle]`; an absent section falls back
+/// to a single `node` source under `GasOracleMode::Median`.
+#[derive(Debug, Deserialize, Default)]
+struct GasOracleFileEntry {
+    mode: Option<String>,
+    tier: Option<String>,
+    #[serde(default)]
+    sources: Vec<GasOracleSourceFileEntry>,
+    cache_ttl_secs: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GasOracleSourceFileEntry {
+    kind: String,
+    url: Option<String>,
+    #[serde(default = "default_gas_source_weight")]
+    weight: f64,
+}
+
+fn default_gas_source_weight() -> f64 {
+    1.0
+}
+
+fn gas_oracle_entry_to_config(entry: GasOracleFileEntry, chain_id: u64) -> Result<gas_oracle::GasOracleConfig> {
+    let mode = match entry.mode.as_deref() {
+        None | Some("median") => gas_oracle::GasOracleMode::Median,
+        Some("weighted_median") => gas_oracle::GasOracleMode::WeightedMedian,
+        Some(other) => return Err(eyre::eyre!("unknown gas_oracle mode '{}' for chain_id {}", other, chain_id)),
+    };
+    let tier = match entry.tier.as_deref() {
+        None | Some("standard") => gas_oracle::GasPriceTier::Standard,
+        Some("low") => gas_oracle::GasPriceTier::Low,
+        Some("fast") => gas_oracle::GasPriceTier::Fast,
+        Some(other) => return Err(eyre::eyre!("unknown gas_oracle tier '{}' for chain_id {}", other, chain_id)),
+    };
+    let sources = if entry.sources.is_empty() {
+        vec![(gas_oracle::GasSourceConfig::Node, 1.0)]
+    } else {
+        let mut sources = Vec::with_capacity(entry.sources.len());
+        for source in entry.sources {
+            let kind = match source.kind.as_str() {
+                "node" => gas_oracle::GasSourceConfig::Node,
+                "fee_history" => gas_oracle::GasSourceConfig::FeeHistory,
+                "http_api" => {
+                    let url = source.url.ok_or_else(|| {
+                        eyre::eyre!("gas_oracle source 'http_api' requires a url for chain_id {}", chain_id)
+                    })?;
+                    gas_oracle::GasSourceConfig::HttpApi { url }
+                }
+                other => return Err(eyre::eyre!("unknown gas_oracle source kind '{}' for chain_id {}", other, chain_id)),
+            };
+            sources.push((kind, source.weight));
+        }
+        sources
+    };
+    Ok(gas_oracle::GasOracleConfig {
+        mode,
+        tier,
+        sources,
+        cache_ttl: Duration::from_secs(entry.cache_ttl_secs.unwrap_or(2)),
+    })
+}
+
+#[derive(Debug, Deserialize)]
+struct ChainlinkFeedFileEntry {
+    symbol: String,
+    address: Address,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChainsFile {
+    chains: Vec<ChainFileEntry>,
+}
+
+/// Parses the registry file, dispatching on extension - `.json` goes
+/// through `serde_json`, anything else is treated as TOML.
+fn load_chains_file(path: &Path) -> Result<ChainsFile> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| eyre::eyre!("failed to read chain config at {}: {}", path.display(), e))?;
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("json") => serde_json::from_str(&raw)
+            .map_err(|e| eyre::eyre!("failed to parse chain config {} as JSON: {}", path.display(), e)),
+        _ => toml::from_str(&raw)
+            .map_err(|e| eyre::eyre!("failed to parse chain config {} as TOML: {}", path.display(), e)),
+    }
+}
+
+/// Validates a chain entry and folds it into the runtime `AppConfig`:
+/// `base_token_symbol` and every Chainlink feed must reference a token
+/// actually declared in this chain's `tokens` list.
+fn chain_entry_to_app_config(
+    entry: ChainFileEntry,
+    simulator_kind: SimulatorKind,
+    pathfinder: PathfinderKind,
+) -> Result<AppConfig> {
+    let mut tokens = HashMap::new();
+    for token in entry.tokens {
+        tokens.insert(token.symbol.clone(), TokenConfig { address: token.address, decimals: token.decimals });
+    }
+    if !tokens.contains_key(&entry.base_token_symbol) {
+        return Err(eyre::eyre!(
+            "base_token_symbol '{}' is not in tokens for chain_id {}",
+            entry.base_token_symbol,
+            entry.chain_id
+        ));
+    }
+
+    let mut dexes = HashMap::new();
+    for dex in entry.dexes {
+        dexes.insert(dex.name.clone(), DexConfig {
+            r#type: dex.r#type,
+            factory: dex.factory,
+            router: dex.router,
+            quoter: dex.quoter,
+            preferred_fees: dex.preferred_fees,
+        });
+    }
+
+    let chainlink_feeds = if entry.chainlink_feeds.is_empty() {
+        None
+    } else {
+        let mut feeds = HashMap::new();
+        for feed in entry.chainlink_feeds {
+            if !tokens.contains_key(&feed.symbol) {
+                return Err(eyre::eyre!(
+                    "chainlink feed references unknown token symbol '{}' for chain_id {}",
+                    feed.symbol,
+                    entry.chain_id
+                ));
+            }
+            feeds.insert(feed.symbol.clone(), ChainlinkFeedConfig { address: feed.address });
+        }
+        Some(feeds)
+    };
+
+    // RPC URLs routinely carry API keys, so let RPC_URL/WS_URL still
+    // override the file's values rather than forcing secrets into the
+    // registry on disk.
+    let rpc_url = env::var("RPC_URL").unwrap_or(entry.rpc_url);
+    let ws_url = env::var("WS_URL").ok().or(entry.ws_url);
+    let gas_oracle = gas_oracle_entry_to_config(entry.gas_oracle, entry.chain_id)?;
+
     Ok(AppConfig {
         rpc_url,
-        min_profit_usd: 10.0,
-        max_hops: 3,
-        scan_loan_amount_usd: 1000.0,
-        base_token_symbol: "WETH".to_string(),
-        tokens: tokens_map,
-        dexes: dex_map,
-        chain_id: 8453, // BASE mainnet
-        flashloan_fee_rate: 0.0009,
-        slippage_tolerance: 0.005,
-        chainlink_feeds: Some(HashMap::new()),
-        fallback_eth_price_usd: 2000.0,
+        ws_url,
+        min_profit_usd: entry.min_profit_usd,
+        max_hops: entry.max_hops,
+        scan_loan_amount_usd: entry.scan_loan_amount_usd,
+        base_token_symbol: entry.base_token_symbol,
+        tokens,
+        dexes,
+        chain_id: entry.chain_id,
+        flashloan_fee_rate: entry.flashloan_fee_rate,
+        slippage_tolerance: entry.slippage_tolerance,
+        chainlink_feeds,
+        fallback_eth_price_usd: entry.fallback_eth_price_usd,
+        simulator_kind,
+        pathfinder,
+        max_priority_fee_per_gas_gwei: entry.max_priority_fee_per_gas_gwei,
+        base_fee_change_denominator: entry.base_fee_change_denominator,
+        elasticity_multiplier: entry.elasticity_multiplier,
+        gas_oracle,
+        max_feed_staleness_secs: entry.max_feed_staleness_secs,
     })
 }
 
+/// Loads `AppConfig` for `requested_chain` (matched against a chain
+/// entry's `name`, case-insensitively, or its `chain_id`) from the
+/// registry at `CHAIN_CONFIG_PATH` (default `config/chains.toml`). Falls
+/// back to the first entry in the file when no chain is requested.
+fn load_app_config(requested_chain: Option<&str>) -> Result<AppConfig> {
+    dotenv().ok();
+    let simulator_kind = match env::var("SIMULATOR_KIND").as_deref() {
+        Ok("evm") => SimulatorKind::Evm,
+        Ok("rpc") | Err(_) => SimulatorKind::Rpc,
+        Ok(other) => {
+            warn!("Unknown SIMULATOR_KIND '{}', falling back to rpc", other);
+            SimulatorKind::Rpc
+        }
+    };
+    let pathfinder = match env::var("PATHFINDER_KIND").as_deref() {
+        Ok("bellman_ford") => PathfinderKind::BellmanFord,
+        Ok("dfs") | Err(_) => PathfinderKind::Dfs,
+        Ok(other) => {
+            warn!("Unknown PATHFINDER_KIND '{}', falling back to dfs", other);
+            PathfinderKind::Dfs
+        }
+    };
+
+    let config_path = env::var("CHAIN_CONFIG_PATH").unwrap_or_else(|_| DEFAULT_CHAIN_CONFIG_PATH.to_string());
+    let chains_file = load_chains_file(Path::new(&config_path))?;
+    if chains_file.chains.is_empty() {
+        return Err(eyre::eyre!("chain config {} has no [[chains]] entries", config_path));
+    }
+
+    let selected = match requested_chain {
+        Some(requested) => chains_file
+            .chains
+            .into_iter()
+            .find(|entry| {
+                entry.name.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(requested))
+                    || entry.chain_id.to_string() == requested
+            })
+            .ok_or_else(|| eyre::eyre!("no chain config entry matches '{}' in {}", requested, config_path))?,
+        None => chains_file.chains.into_iter().next().expect("checked non-empty above"),
+    };
+
+    chain_entry_to_app_config(selected, simulator_kind, pathfinder)
+}
+
 // -------------------
 // Token lookup helpers
 // -------------------
@@ -308,7 +598,7 @@ fn get_token_by_addr(addr: &Address, config: &AppConfig) -> Option<Token> {
 // Unit parsing helpers
 // -------------------
 
-fn parse_units<T: Into<f64>>(amount: T, decimals: u32) -> Result<U256> {
+pub(crate) fn parse_units<T: Into<f64>>(amount: T, decimals: u32) -> Result<U256> {
     let float = amount.into();
     let scaled = float * 10f64.powi(decimals as i32);
     let int_str = format!("{:.0}", scaled);
@@ -358,7 +648,7 @@ async fn get_v2_pair_address(
     }
 }
 
-async fn get_v2_pool_data(pair_addr: Address, client: Client) -> Result<Option<(V2PoolData, u64)>> {
+pub(crate) async fn get_v2_pool_data(pair_addr: Address, client: Client) -> Result<Option<(V2PoolData, u64)>> {
     {
         let cache = POOL_DATA_CACHE.lock().await;
         if let Some(pool_data) = cache.get(&pair_addr) {
@@ -428,7 +718,7 @@ async fn get_v3_pool_address(
     }
 }
 
-async fn get_amount_out_v3_quote(
+pub(crate) async fn get_amount_out_v3_quote(
     amt_in: U256,
     t_in: Address,
     t_out: Address,
@@ -453,7 +743,7 @@ async fn get_amount_out_v3_quote(
     }
 }
 
-fn get_amount_out_v2_local(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
+pub(crate) fn get_amount_out_v2_local(amount_in: U256, reserve_in: U256, reserve_out: U256) -> U256 {
     if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
         return U256::zero();
     }
@@ -476,6 +766,23 @@ struct SimulateResult {
     error: Option<String>,
 }
 
+/// Builds the `SwapSimulator` a hop on `dex_config` should quote through,
+/// per `AppConfig::simulator_kind`. Built fresh per hop rather than cached.
+/// `RpcSimulator`'s V2 quotes still read through `POOL_DATA_CACHE`; an
+/// `EvmSimulator`'s block snapshot lives only as long as the simulator, so
+/// it is shared by one hop's fee-tier quotes and not across hops.
+fn build_simulator(
+    kind: SimulatorKind,
+    dex_config: &DexConfig,
+    client: Client,
+    scan_block: u64,
+) -> Arc<dyn sim::SwapSimulator> {
+    match kind {
+        SimulatorKind::Evm => Arc::new(sim::EvmSimulator::new(client, dex_config.quoter, scan_block)),
+        SimulatorKind::Rpc => Arc::new(sim::RpcSimulator::new(client, dex_config.quoter)),
+    }
+}
+
 async fn simulate_swap_step(
     amount_in: U256,
     token_in: &Token,
@@ -483,6 +790,7 @@ async fn simulate_swap_step(
     dex_name: &str,
     dex_config: &DexConfig,
     client: Client,
+    simulator: Arc<dyn sim::SwapSimulator>,
 ) -> SimulateResult {
     let mut result = SimulateResult {
         output_amount: U256::zero(),
@@ -504,16 +812,12 @@ async fn simulate_swap_step(
                 match get_v2_pair_address(token_in.address, token_out.address, factory, client.clone()).await {
                     Ok(Some(pair_addr)) => {
                         result.pool_address = Some(pair_addr);
-                        match get_v2_pool_data(pair_addr, client.clone()).await {
-                            Ok(Some((pool_data, _))) => {
-                                let (res_in, res_out) = if token_in.address == pool_data.token0 {
-                                    (pool_data.reserve0, pool_data.reserve1)
-                                } else {
-                                    (pool_data.reserve1, pool_data.reserve0)
-                                };
-                                result.output_amount = get_amount_out_v2_local(amount_in, res_in, res_out);
-                            }
-                            _ => result.error = Some("V2 Pool data unavailable".into()),
+                        match simulator
+                            .amount_out(amount_in, token_in.address, token_out.address, None, pair_addr)
+                            .await
+                        {
+                            Ok(amount) => result.output_amount = amount,
+                            Err(e) => result.error = Some(format!("V2 simulation failed: {}", e)),
                         }
                     }
                     _ => result.error = Some("V2 Pair not found".into()),
@@ -523,7 +827,7 @@ async fn simulate_swap_step(
             }
         }
         "V3" => {
-            if let (Some(factory), Some(quoter)) = (dex_config.factory, dex_config.quoter) {
+            if let (Some(factory), Some(_quoter)) = (dex_config.factory, dex_config.quoter) {
                 info!("Checking pair: {} ↔ {} on {}", token_in.symbol, token_out.symbol, dex_name);
                 let fees = dex_config.preferred_fees.as_ref().map_or(&[100u32, 500, 3000, 10000][..], |v| v.as_slice());
                 let mut best_output = U256::zero();
@@ -547,9 +851,12 @@ async fn simulate_swap_step(
                 }
 
                 let quote_tasks: Vec<_> = valid_pools.into_iter().map(|(fee, pool_addr)| {
-                    let client_clone = client.clone();
+                    let simulator = simulator.clone();
                     async move {
-                        match get_amount_out_v3_quote(amount_in, token_in.address, token_out.address, fee, quoter, client_clone).await {
+                        match simulator
+                            .amount_out(amount_in, token_in.address, token_out.address, Some(fee), pool_addr)
+                            .await
+                        {
                             Ok(amount) => Some((fee, pool_addr, amount)),
                             Err(_) => None,
                         }
@@ -583,7 +890,7 @@ async fn simulate_swap_step(
 }
 
 // -------------------
-// DFS Pathfinding
+// Pathfinding
 // -------------------
 
 #[derive(Clone, Debug)]
@@ -598,7 +905,71 @@ struct PathState {
 type SharedOpps = Arc<Mutex<Vec<ArbOpportunityRaw>>>;
 type BoxedResult = Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 
+/// Entry point used by `main` - dispatches to whichever pathfinder
+/// `config.pathfinder` selects.
 async fn find_arbitrage_paths(client: Client, config: Arc<AppConfig>) -> Result<Vec<ArbOpportunityRaw>> {
+    match config.pathfinder {
+        PathfinderKind::Dfs => find_arbitrage_paths_dfs(client, config).await,
+        PathfinderKind::BellmanFord => find_arbitrage_paths_bellman_ford(client, config).await,
+    }
+}
+
+/// Checks whether closing a path back at `base_token` is profitable once
+/// gas and the flashloan fee are netted out, and if so records it. Shared
+/// by both pathfinders so the profit/gas/fee accounting - and the shape
+/// of the resulting `ArbOpportunityRaw` - stays identical regardless of
+/// which strategy found the path.
+async fn finalize_if_profitable(
+    path: &[SwapStepRaw],
+    base_token: &Token,
+    start_amount: U256,
+    current_amount: U256,
+    client: &Client,
+    config: &AppConfig,
+    opps: &SharedOpps,
+) -> Result<()> {
+    let gross_profit = current_amount.saturating_sub(start_amount);
+    if gross_profit.is_zero() {
+        return Ok(());
+    }
+    let gas_units = 500_000u64 + (path.len() as u64 * 150_000);
+    let gas_cost_base = estimate_gas_cost_in_base(base_token, gas_units, client.clone(), config).await.unwrap_or(U256::max_value());
+    let fee_rate_num = U256::from((config.flashloan_fee_rate * 10000.0) as u128);
+    let fee_rate_den = U256::from(10000);
+    let flashloan_fee = start_amount.checked_mul(fee_rate_num).unwrap_or_default() / fee_rate_den;
+    let net_profit = gross_profit.saturating_sub(flashloan_fee).saturating_sub(gas_cost_base);
+    if net_profit > U256::zero() {
+        let net_profit_usd = calculate_usd_value(net_profit, base_token, client, config).await.unwrap_or(0.0);
+        if net_profit_usd >= config.min_profit_usd {
+            let path_tokens: Vec<Token> = std::iter::once(base_token.clone())
+                .chain(path.iter().filter_map(|s| get_token_by_addr(&s.token_out_addr, config)))
+                .collect();
+            let opportunity = ArbOpportunityRaw {
+                estimated_net_profit: net_profit.to_string(),
+                loan_amount: start_amount.to_string(),
+                min_return: (current_amount * U256::from(((1.0 - config.slippage_tolerance) * 10000.0) as u64) / U256::from(10000)).to_string(),
+                swap_path: path.to_vec(),
+                token_path_symbols: path_tokens.iter().map(|t| t.symbol.clone()).collect(),
+                token_path_addresses: path_tokens.iter().map(|t| t.address.to_string()).collect(),
+                path: path.iter().map(|s| s.dex_name.clone()).collect(),
+                estimated_gas_units: gas_units.to_string(),
+                gas_estimate_fallback_used: gas_cost_base == U256::max_value(),
+            };
+            opps.lock().await.push(opportunity);
+            info!("Found profitable path: {} → {} | Profit: ${:.2}",
+                  base_token.symbol,
+                  path.iter().map(|s| s.token_out_symbol.clone()).collect::<Vec<_>>().join(" → "),
+                  net_profit_usd);
+        }
+    }
+    Ok(())
+}
+
+// -------------------
+// DFS Pathfinding
+// -------------------
+
+async fn find_arbitrage_paths_dfs(client: Client, config: Arc<AppConfig>) -> Result<Vec<ArbOpportunityRaw>> {
     let start_time = Instant::now();
     let base_token = get_token(&config.base_token_symbol, &config)
         .ok_or_else(|| eyre::eyre!("Base token not found in config"))?;
@@ -618,8 +989,12 @@ async fn find_arbitrage_paths(client: Client, config: Arc<AppConfig>) -> Result<
         depth: 0,
         start_amount: scan_loan,
     };
+    // Pinned once so every `EvmSimulator` quote in this scan reads the same
+    // block - otherwise later quotes could race a moving chain head and the
+    // profit math across hops would no longer be internally consistent.
+    let scan_block = client.get_block_number().await?.as_u64();
     info!("DFS Start. Base: {}, Loan: {}", base_token.symbol, format_units(scan_loan, base_token.decimals as i32)?);
-    dfs_explore_sequential(init_state, client.clone(), config.clone(), opps.clone(), visited.clone()).await?;
+    dfs_explore_sequential(init_state, client.clone(), config.clone(), opps.clone(), visited.clone(), scan_block).await?;
     let final_ops = opps.lock().await.clone();
     info!("Scan done in {:.2}s. Found {} paths.", start_time.elapsed().as_secs_f64(), final_ops.len());
     Ok(final_ops)
@@ -631,6 +1006,7 @@ fn dfs_explore_sequential(
     config: Arc<AppConfig>,
     opps: SharedOpps,
     visited: Arc<Mutex<HashSet<String>>>,
+    scan_block: u64,
 ) -> BoxedResult {
     Box::pin(async move {
         if state.depth >= config.max_hops {
@@ -643,40 +1019,16 @@ fn dfs_explore_sequential(
         let base_token = get_token(&config.base_token_symbol, &config)
             .ok_or_else(|| eyre::eyre!("Base token not found"))?;
         if state.current_token.address == base_token.address && state.depth > 0 {
-            let gross_profit = state.current_amount.saturating_sub(state.start_amount);
-            if !gross_profit.is_zero() {
-                let gas_units = 500_000u64 + (state.depth as u64 * 150_000);
-                let gas_cost_base = estimate_gas_cost_in_base(&base_token, gas_units, client.clone(), &config).await.unwrap_or(U256::max_value());
-                let fee_rate_num = U256::from((config.flashloan_fee_rate * 10000.0) as u128);
-                let fee_rate_den = U256::from(10000);
-                let flashloan_fee = state.start_amount.checked_mul(fee_rate_num).unwrap_or_default() / fee_rate_den;
-                let net_profit = gross_profit.saturating_sub(flashloan_fee).saturating_sub(gas_cost_base);
-                if net_profit > U256::zero() {
-                    let net_profit_usd = calculate_usd_value(net_profit, &base_token, &client, &config).await.unwrap_or(0.0);
-                    if net_profit_usd >= config.min_profit_usd {
-                        let path_tokens: Vec<Token> = std::iter::once(base_token.clone())
-                            .chain(state.path.iter().filter_map(|s| get_token_by_addr(&s.token_out_addr, &config)))
-                            .collect();
-                        let opportunity = ArbOpportunityRaw {
-                            estimated_net_profit: net_profit.to_string(),
-                            loan_amount: state.start_amount.to_string(),
-                            min_return: (state.current_amount * U256::from(((1.0 - config.slippage_tolerance) * 10000.0) as u64) / U256::from(10000)).to_string(),
-                            swap_path: state.path.clone(),
-                            token_path_symbols: path_tokens.iter().map(|t| t.symbol.clone()).collect(),
-                            token_path_addresses: path_tokens.iter().map(|t| t.address.to_string()).collect(),
-                            path: state.path.iter().map(|s| s.dex_name.clone()).collect(),
-                            estimated_gas_units: gas_units.to_string(),
-                            gas_estimate_fallback_used: gas_cost_base == U256::max_value(),
-                        };
-                        opps.lock().await.push(opportunity);
-                        info!("Found profitable path: {} → {} | Profit: ${:.2}",
-                              base_token.symbol,
-                              state.path.iter().map(|s| s.token_out_symbol.clone()).collect::<Vec<_>>().join(" → "),
-                              net_profit_usd);
-                    }
-                }
-                return Ok(());
-            }
+            finalize_if_profitable(
+                &state.path,
+                &base_token,
+                state.start_amount,
+                state.current_amount,
+                &client,
+                &config,
+                &opps,
+            ).await?;
+            return Ok(());
         }
         let mut next_states = Vec::new();
         for next_sym in config.tokens.keys() {
@@ -685,6 +1037,7 @@ fn dfs_explore_sequential(
             }
             if let Some(next_tok) = get_token(next_sym, &config) {
                 for (dex_name, dex_cfg) in &config.dexes {
+                    let simulator = build_simulator(config.simulator_kind, dex_cfg, client.clone(), scan_block);
                     let sim_result = simulate_swap_step(
                         state.current_amount,
                         &state.current_token,
@@ -692,6 +1045,7 @@ fn dfs_explore_sequential(
                         dex_name,
                         &dex_cfg,
                         client.clone(),
+                        simulator,
                     ).await;
                     if sim_result.output_amount > U256::zero() && sim_result.pool_address.is_some() {
                         let next_step = SwapStepRaw {
@@ -720,12 +1074,278 @@ fn dfs_explore_sequential(
             }
         }
         for next_state in next_states {
-            dfs_explore_sequential(next_state, client.clone(), config.clone(), opps.clone(), visited.clone()).await?;
+            dfs_explore_sequential(next_state, client.clone(), config.clone(), opps.clone(), visited.clone(), scan_block).await?;
         }
         Ok(())
     })
 }
 
+// -------------------
+// Bellman-Ford Pathfinding
+// -------------------
+
+/// Longest negative cycle Bellman-Ford is allowed to recover, guarding
+/// against pathologically long loops that would be too gas-expensive to
+/// execute atomically even if they were theoretically profitable.
+const MAX_CYCLE_LEN: usize = 6;
+
+/// One directed `token_in -> token_out` edge in the marginal-rate graph,
+/// carrying enough provenance (dex, pool, fee tier) to reconstruct a
+/// `SwapStepRaw` once a cycle through it is confirmed profitable.
+#[derive(Clone, Debug)]
+struct ArbEdge {
+    from: usize,
+    to: usize,
+    weight: f64,
+    dex_name: String,
+    pool_address: Address,
+    is_v3: bool,
+    fee: Option<u32>,
+}
+
+/// `-ln(amount_out / amount_in)`, so that summing weights along a path
+/// gives `-ln(product of exchange rates)`: a cycle is profitable exactly
+/// when that product exceeds 1, i.e. when the summed weight is negative.
+fn edge_weight(amount_in: U256, token_in: &Token, amount_out: U256, token_out: &Token) -> Option<f64> {
+    if amount_in.is_zero() || amount_out.is_zero() {
+        return None;
+    }
+    let in_f = amount_in.to_f64_lossy() / 10f64.powi(token_in.decimals as i32);
+    let out_f = amount_out.to_f64_lossy() / 10f64.powi(token_out.decimals as i32);
+    if !(in_f > 0.0) || !(out_f > 0.0) {
+        return None;
+    }
+    Some(-(out_f / in_f).ln())
+}
+
+/// Builds the full edge set once per scan block: every ordered token
+/// pair, on every configured dex, probed with a one-unit trade and
+/// reusing `simulate_swap_step` so the quoting logic (and its
+/// `SimulatorKind`) matches the DFS pathfinder exactly.
+async fn build_edge_set(nodes: &[Token], client: &Client, config: &AppConfig, scan_block: u64) -> Vec<ArbEdge> {
+    let mut edges = Vec::new();
+    for (i, token_in) in nodes.iter().enumerate() {
+        let probe_amount = U256::from(10).pow(U256::from(token_in.decimals));
+        for (j, token_out) in nodes.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            for (dex_name, dex_cfg) in &config.dexes {
+                let simulator = build_simulator(config.simulator_kind, dex_cfg, client.clone(), scan_block);
+                let sim_result = simulate_swap_step(
+                    probe_amount,
+                    token_in,
+                    token_out,
+                    dex_name,
+                    dex_cfg,
+                    client.clone(),
+                    simulator,
+                ).await;
+                if sim_result.output_amount.is_zero() || sim_result.pool_address.is_none() {
+                    continue;
+                }
+                if let Some(weight) = edge_weight(probe_amount, token_in, sim_result.output_amount, token_out) {
+                    edges.push(ArbEdge {
+                        from: i,
+                        to: j,
+                        weight,
+                        dex_name: dex_name.clone(),
+                        pool_address: sim_result.pool_address.unwrap(),
+                        is_v3: sim_result.is_v3,
+                        fee: sim_result.fee,
+                    });
+                }
+            }
+        }
+    }
+    edges
+}
+
+/// Runs Bellman-Ford from `source` and, if a negative cycle reachable
+/// from it exists, returns its edges in trade order. Standard relax-`V-1`-
+/// times-then-check-once detection: any edge that still relaxes on the
+/// `V`-th pass lies on a negative cycle, and walking predecessor edges
+/// back `V` more hops from it is guaranteed to land on a node that is
+/// actually part of that cycle rather than just downstream of it.
+fn bellman_ford_negative_cycle(nodes: &[Token], edges: &[ArbEdge], source: usize) -> Option<Vec<usize>> {
+    let n = nodes.len();
+    if n == 0 {
+        return None;
+    }
+    let mut dist = vec![f64::INFINITY; n];
+    let mut pred_edge: Vec<Option<usize>> = vec![None; n];
+    dist[source] = 0.0;
+
+    let mut relaxed_edge = None;
+    for iter in 0..n {
+        relaxed_edge = None;
+        for (edge_idx, edge) in edges.iter().enumerate() {
+            if dist[edge.from].is_finite() && dist[edge.from] + edge.weight < dist[edge.to] - 1e-9 {
+                dist[edge.to] = dist[edge.from] + edge.weight;
+                pred_edge[edge.to] = Some(edge_idx);
+                if iter == n - 1 {
+                    relaxed_edge = Some(edge_idx);
+                }
+            }
+        }
+    }
+
+    let relaxed_edge_idx = relaxed_edge?;
+    let mut node = edges[relaxed_edge_idx].to;
+    for _ in 0..n {
+        node = edges[pred_edge[node]?].from;
+    }
+
+    let cycle_start = node;
+    let mut cycle_edges = Vec::new();
+    let mut cur = cycle_start;
+    loop {
+        let edge_idx = pred_edge[cur]?;
+        cycle_edges.push(edge_idx);
+        cur = edges[edge_idx].from;
+        if cur == cycle_start {
+            break;
+        }
+        if cycle_edges.len() >= MAX_CYCLE_LEN {
+            return None;
+        }
+    }
+    cycle_edges.reverse();
+    Some(cycle_edges)
+}
+
+/// Re-simulates a candidate cycle at the real `scan_loan` size through
+/// `simulate_swap_step`, since Bellman-Ford's edge weights come from
+/// one-unit probe trades and ignore the slippage a full-size trade would
+/// incur. Records the cycle via `finalize_if_profitable` if it still
+/// closes in profit afterward.
+async fn simulate_and_record_cycle(
+    cycle_edge_idxs: &[usize],
+    edges: &[ArbEdge],
+    nodes: &[Token],
+    base_token: &Token,
+    scan_loan: U256,
+    scan_block: u64,
+    client: &Client,
+    config: &AppConfig,
+    opps: &SharedOpps,
+) -> Result<()> {
+    let mut current_amount = scan_loan;
+    let mut path = Vec::with_capacity(cycle_edge_idxs.len());
+    for &edge_idx in cycle_edge_idxs {
+        let edge = &edges[edge_idx];
+        let token_in = &nodes[edge.from];
+        let token_out = &nodes[edge.to];
+        let dex_cfg = config.dexes.get(&edge.dex_name)
+            .ok_or_else(|| eyre::eyre!("Dex {} missing from config during cycle replay", edge.dex_name))?;
+        let simulator = build_simulator(config.simulator_kind, dex_cfg, client.clone(), scan_block);
+        let sim_result = simulate_swap_step(
+            current_amount,
+            token_in,
+            token_out,
+            &edge.dex_name,
+            dex_cfg,
+            client.clone(),
+            simulator,
+        ).await;
+        if sim_result.output_amount.is_zero() || sim_result.pool_address.is_none() {
+            // Slippage at the real loan size killed a hop Bellman-Ford's
+            // marginal-rate probe thought was fine - discard the whole
+            // candidate rather than pretend the loop still closes.
+            return Ok(());
+        }
+        path.push(SwapStepRaw {
+            dex_name: edge.dex_name.clone(),
+            token_in_addr: token_in.address,
+            token_out_addr: token_out.address,
+            pool_address: sim_result.pool_address.unwrap(),
+            is_v3: sim_result.is_v3,
+            fee: sim_result.fee,
+            token_in_symbol: token_in.symbol.clone(),
+            token_out_symbol: token_out.symbol.clone(),
+            input_amount_sim: current_amount,
+            output_amount_sim: sim_result.output_amount,
+        });
+        current_amount = sim_result.output_amount;
+    }
+
+    finalize_if_profitable(&path, base_token, scan_loan, current_amount, client, config, opps).await
+}
+
+async fn find_arbitrage_paths_bellman_ford(client: Client, config: Arc<AppConfig>) -> Result<Vec<ArbOpportunityRaw>> {
+    let start_time = Instant::now();
+    let base_token = get_token(&config.base_token_symbol, &config)
+        .ok_or_else(|| eyre::eyre!("Base token not found in config"))?;
+    let base_price = get_usd_price_async(&base_token.symbol, &client, &config).await.unwrap_or(1.0);
+    if base_price <= 0.0 {
+        return Err(eyre::eyre!("Invalid base price"));
+    }
+    let loan_f = config.scan_loan_amount_usd / base_price;
+    let scan_loan: U256 = parse_units(loan_f, base_token.decimals as u32)?.into();
+    // Pinned once, same as the DFS pathfinder, so every probe and every
+    // cycle replay in this scan reads identical chain state.
+    let scan_block = client.get_block_number().await?.as_u64();
+
+    let nodes: Vec<Token> = config.tokens.keys().filter_map(|sym| get_token(sym, &config)).collect();
+    let source = nodes
+        .iter()
+        .position(|t| t.address == base_token.address)
+        .ok_or_else(|| eyre::eyre!("Base token missing from node set"))?;
+
+    let mut remaining_edges = build_edge_set(&nodes, &client, &config, scan_block).await;
+    info!("Bellman-Ford edge set built: {} edges across {} tokens", remaining_edges.len(), nodes.len());
+
+    let opps: SharedOpps = Arc::new(Mutex::new(Vec::new()));
+    let mut seen_cycles: HashSet<Vec<usize>> = HashSet::new();
+
+    // One Bellman-Ford run surfaces at most one negative cycle; drop its
+    // weakest edge and re-run to look for others, bounded the same way
+    // the DFS pathfinder bounds its search depth.
+    for _ in 0..config.max_hops {
+        let Some(cycle_edge_idxs) = bellman_ford_negative_cycle(&nodes, &remaining_edges, source) else {
+            break;
+        };
+        let cycle_nodes: Vec<usize> = cycle_edge_idxs.iter().map(|&idx| remaining_edges[idx].from).collect();
+        if !seen_cycles.insert(cycle_nodes.clone()) {
+            break;
+        }
+
+        if let Some(base_pos) = cycle_nodes.iter().position(|&n| n == source) {
+            let rotated: Vec<usize> = cycle_edge_idxs[base_pos..]
+                .iter()
+                .chain(cycle_edge_idxs[..base_pos].iter())
+                .copied()
+                .collect();
+            simulate_and_record_cycle(
+                &rotated,
+                &remaining_edges,
+                &nodes,
+                &base_token,
+                scan_loan,
+                scan_block,
+                &client,
+                &config,
+                &opps,
+            ).await?;
+        }
+
+        let weakest = cycle_edge_idxs
+            .iter()
+            .max_by(|&&a, &&b| remaining_edges[a].weight.partial_cmp(&remaining_edges[b].weight).unwrap())
+            .copied();
+        match weakest {
+            Some(idx) => {
+                remaining_edges.remove(idx);
+            }
+            None => break,
+        }
+    }
+
+    let final_opps = opps.lock().await.clone();
+    info!("Bellman-Ford scan done in {:.2}s. Found {} paths.", start_time.elapsed().as_secs_f64(), final_opps.len());
+    Ok(final_opps)
+}
+
 // -------------------
 // Price & Gas Cost Helpers
 // -------------------
@@ -763,6 +1383,47 @@ async fn get_chainlink_feed(symbol: &str, client: Client, config: &AppConfig) ->
     Ok(None)
 }
 
+/// Validates a `latestRoundData()` result and decodes it to a USD price,
+/// or returns an error describing why the round was rejected: a
+/// non-positive answer, an `answeredInRound` behind `roundId` (the round
+/// was carried over rather than genuinely updated), or an `updatedAt`
+/// older than `config.max_feed_staleness_secs` measured against the
+/// latest block's timestamp.
+async fn validate_round_data(
+    round_id: U256,
+    answer: I256,
+    updated_at: U256,
+    answered_in_round: U256,
+    decimals: u8,
+    client: &Client,
+    config: &AppConfig,
+) -> Result<f64> {
+    if answer <= I256::zero() {
+        return Err(eyre::eyre!("non-positive answer {}", answer));
+    }
+    if answered_in_round < round_id {
+        return Err(eyre::eyre!(
+            "round carried over: answeredInRound {} < roundId {}",
+            answered_in_round,
+            round_id
+        ));
+    }
+    let latest_block = client
+        .get_block(BlockNumber::Latest)
+        .await?
+        .ok_or_else(|| eyre::eyre!("node returned no latest block"))?;
+    let age_secs = latest_block.timestamp.saturating_sub(updated_at);
+    if age_secs > U256::from(config.max_feed_staleness_secs) {
+        return Err(eyre::eyre!(
+            "stale answer: updatedAt is {}s old (max {}s)",
+            age_secs,
+            config.max_feed_staleness_secs
+        ));
+    }
+    let price_scaled = answer.as_u128() as f64;
+    Ok(price_scaled / 10f64.powi(decimals as i32))
+}
+
 async fn get_usd_price_async(symbol: &str, client: &Client, config: &AppConfig) -> Result<f64> {
     let upper_sym = symbol.to_uppercase();
     if ["USDC", "USDT", "DAI", "BUSD"].contains(&upper_sym.as_str()) {
@@ -772,9 +1433,10 @@ async fn get_usd_price_async(symbol: &str, client: &Client, config: &AppConfig)
         if let Ok(Some((feed_info, _))) = get_chainlink_feed(&upper_sym, client.clone(), config).await {
             match feed_info.contract.latest_round_data().call().await {
                 Ok(data) => {
-                    let price_scaled = data.1.as_u128() as f64;
-                    let divisor = 10f64.powi(feed_info.decimals as i32);
-                    return Ok(price_scaled / divisor);
+                    match validate_round_data(data.0, data.1, data.3, data.4, feed_info.decimals, client, config).await {
+                        Ok(price) => return Ok(price),
+                        Err(e) => warn!("Rejecting {} Chainlink round: {}", upper_sym, e),
+                    }
                 },
                 Err(e) => {
                     warn!("Failed to get latest price data: {}", e);
@@ -785,9 +1447,13 @@ async fn get_usd_price_async(symbol: &str, client: &Client, config: &AppConfig)
         if let Ok(Some((feed_info, _))) = get_chainlink_feed(&eth_sym, client.clone(), config).await {
             match feed_info.contract.latest_round_data().call().await {
                 Ok(data) => {
-                    let token_eth_price = data.1.as_u128() as f64 / 10f64.powi(feed_info.decimals as i32);
-                    if let Ok(eth_usd) = get_eth_price_async(client.clone(), config).await {
-                        return Ok(token_eth_price * eth_usd);
+                    match validate_round_data(data.0, data.1, data.3, data.4, feed_info.decimals, client, config).await {
+                        Ok(token_eth_price) => {
+                            if let Ok(eth_usd) = get_eth_price_async(client.clone(), config).await {
+                                return Ok(token_eth_price * eth_usd);
+                            }
+                        }
+                        Err(e) => warn!("Rejecting {} Chainlink round: {}", eth_sym, e),
                     }
                 },
                 Err(_) => {}
@@ -807,9 +1473,10 @@ async fn get_eth_price_async(client: Client, config: &AppConfig) -> Result<f64>
             let decimals = 8;
             match feed_contract.latest_round_data().call().await {
                 Ok(data) => {
-                    let price_scaled = data.1.as_u128() as f64;
-                    let divisor = 10f64.powi(decimals as i32);
-                    return Ok(price_scaled / divisor);
+                    match validate_round_data(data.0, data.1, data.3, data.4, decimals, &client, config).await {
+                        Ok(price) => return Ok(price),
+                        Err(e) => warn!("Rejecting ETH Chainlink round: {}", e),
+                    }
                 },
                 Err(e) => {
                     warn!("Failed to get ETH price data: {}", e);
@@ -820,13 +1487,14 @@ async fn get_eth_price_async(client: Client, config: &AppConfig) -> Result<f64>
     Ok(config.fallback_eth_price_usd)
 }
 
-async fn estimate_gas_cost_in_eth(units: u64, client: &Client) -> Result<U256> {
-    let gas_price = client.get_gas_price().await?;
-    Ok(U256::from(units) * gas_price)
-}
-
+/// Prices `units` of gas by asking the configured `GasOracle` for the
+/// current per-gas price at `config.gas_oracle.tier` (a node-only
+/// EIP-1559 base-fee + priority-fee estimate aggregated against whatever
+/// other sources are configured), rather than trusting a single RPC call.
 async fn estimate_gas_cost_in_base(base_token: &Token, units: u64, client: Client, config: &AppConfig) -> Result<U256> {
-    let cost_eth = estimate_gas_cost_in_eth(units, &client).await?;
+    let oracle = gas_oracle::GasOracle::new(config.gas_oracle.clone());
+    let gas_price = oracle.quote(&client, config).await?;
+    let cost_eth = U256::from(units) * gas_price;
     convert_eth_cost_to_base(cost_eth, base_token, client, config).await
 }
 
@@ -836,8 +1504,8 @@ async fn convert_eth_cost_to_base(cost_eth: U256, base_token: &Token, client: Cl
     if eth_price_usd <= 0.0 || base_price_usd <= 0.0 {
         return Err(eyre::eyre!("Invalid prices for gas conversion"));
     }
-    let eth_price_wad: U256 = parse_units(eth_price_usd, 18)?.into();
-    let base_price_wad: U256 = parse_units(base_price_usd, 18)?.into();
+    let eth_price_wad = numeric::u256_from_f64_saturating(eth_price_usd * 1e18);
+    let base_price_wad = numeric::u256_from_f64_saturating(base_price_usd * 1e18);
     if base_price_wad.is_zero() {
         return Err(eyre::eyre!("Base price WAD is zero"));
     }
@@ -858,8 +1526,29 @@ async fn calculate_usd_value(amount_wei: U256, token: &Token, client: &Client, c
     if price.is_nan() || price <= 0.0 {
         return Err(eyre::eyre!("Invalid token price"));
     }
-    let value_float = amount_wei.to_f64_lossy() / 10f64.powi(token.decimals as i32);
-    Ok(value_float * price)
+
+    // Same WAD-precision trick as `convert_eth_cost_to_base`: scale the
+    // price into an exact `U256` and do the token-decimals division on
+    // integers, instead of converting `amount_wei` at full wei magnitude
+    // through `to_f64_lossy` first and losing precision past 2^53.
+    let price_wad = numeric::u256_from_f64_saturating(price * 1e18);
+    let scale_factor = U256::from(10).pow(U256::from(token.decimals));
+    let exp18 = U256::from(10).pow(U256::from(18));
+
+    // Extra micro-dollar precision so the one remaining `U256` -> `f64`
+    // conversion, on the already-scaled-down result, keeps a few decimal
+    // places instead of truncating to whole dollars.
+    let micro_usd_scale = U256::from(1_000_000);
+
+    let numerator = amount_wei
+        .checked_mul(price_wad).ok_or_else(|| eyre::eyre!("Overflow"))?
+        .checked_mul(micro_usd_scale).ok_or_else(|| eyre::eyre!("Overflow"))?;
+    let denominator = scale_factor.checked_mul(exp18).ok_or_else(|| eyre::eyre!("Overflow"))?;
+    if denominator.is_zero() {
+        return Err(eyre::eyre!("Division by zero"));
+    }
+
+    Ok((numerator / denominator).to_f64_lossy() / 1e6)
 }
 
 // -------------------
@@ -874,7 +1563,7 @@ async fn main() -> Result<()> {
         .init();
 
     let args = CliArgs::parse();
-    let mut config = load_app_config()?;
+    let mut config = load_app_config(args.chain.as_deref())?;
 
     if let Some(min_profit) = args.min_profit_usd_override {
         info!("Overriding min profit: ${:.2} → ${:.2}", config.min_profit_usd, min_profit);
@@ -885,16 +1574,23 @@ async fn main() -> Result<()> {
         config.max_hops = max_hops;
     }
 
-    let provider = Provider::<Http>::try_from(&config.rpc_url)?
-        .interval(Duration::from_millis(100));
+    if args.watch && args.block.is_some() {
+        return Err(eyre::eyre!("--watch cannot be combined with --block"));
+    }
 
-    let client: Client = if let Some(block_number) = args.block {
+    if args.watch {
+        let endpoint = config.ws_url.clone().unwrap_or_else(|| config.rpc_url.clone());
+        return run_loop(endpoint, Arc::new(config)).await;
+    }
+
+    let client_transport = transport::ClientTransport::connect(&config.rpc_url).await?;
+    let mut provider = Provider::new(client_transport).interval(Duration::from_millis(100));
+
+    if let Some(block_number) = args.block {
         info!("Running simulation at historical block {}", block_number);
-        let provider_with_block = provider.clone().with_sender(Address::zero());
-        Arc::new(provider_with_block)
-    } else {
-        Arc::new(provider)
-    };
+        provider = provider.with_sender(Address::zero());
+    }
+    let client: Client = Arc::new(provider);
 
     match client.client_version().await {
         Ok(version) => info!("Connected to node: {}", version),
@@ -902,6 +1598,7 @@ async fn main() -> Result<()> {
     }
 
     let config_arc = Arc::new(config);
+
     match find_arbitrage_paths(client.clone(), config_arc.clone()).await {
         Ok(opportunities) => {
             let json = serde_json::to_string_pretty(&opportunities)?;
@@ -915,3 +1612,77 @@ async fn main() -> Result<()> {
         }
     }
 }
+
+/// `--watch` daemon loop: connects to `endpoint`, subscribes to new block
+/// headers, and re-runs `find_arbitrage_paths` on every new block,
+/// clearing the per-block pool/feed caches first so each pass reads
+/// fresh state instead of whatever the previous block's scan left
+/// cached. Opportunities are emitted as a JSON stream - one object per
+/// line - so downstream consumers can tail the process output. A lost
+/// connection or a subscription that ends reconnects with exponential
+/// backoff rather than terminating the process.
+async fn run_loop(endpoint: String, config: Arc<AppConfig>) -> Result<()> {
+    const MAX_BACKOFF: Duration = Duration::from_secs(30);
+    let mut backoff = Duration::from_secs(1);
+
+    loop {
+        let client: Client = match transport::ClientTransport::connect(&endpoint).await {
+            Ok(transport) => {
+                if !transport.supports_subscriptions() {
+                    return Err(eyre::eyre!(
+                        "--watch requires a WebSocket or IPC endpoint, got '{}'",
+                        endpoint
+                    ));
+                }
+                Arc::new(Provider::new(transport).interval(Duration::from_millis(100)))
+            }
+            Err(e) => {
+                warn!("Failed to connect to {}: {} (retrying in {:?})", endpoint, e, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+                continue;
+            }
+        };
+
+        match client.client_version().await {
+            Ok(version) => info!("Connected to node: {}", version),
+            Err(e) => warn!("Failed to get node info: {}", e),
+        }
+
+        let mut headers = match client.subscribe_blocks().await {
+            Ok(headers) => headers,
+            Err(e) => {
+                warn!("Failed to subscribe to new heads: {} (retrying in {:?})", e, backoff);
+                tokio::time::sleep(backoff).await;
+                backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+                continue;
+            }
+        };
+        info!("Subscribed to new block headers; entering continuous scan loop");
+        backoff = Duration::from_secs(1);
+
+        while let Some(block) = headers.next().await {
+            let block_number = block.number.map(|n| n.as_u64()).unwrap_or_default();
+            info!("New block {}: invalidating caches and re-scanning", block_number);
+            POOL_DATA_CACHE.lock().await.clear();
+            FEED_INFO_CACHE.lock().await.clear();
+
+            match find_arbitrage_paths(client.clone(), config.clone()).await {
+                Ok(opportunities) => {
+                    info!("Block {}: found {} arbitrage paths", block_number, opportunities.len());
+                    for opportunity in &opportunities {
+                        match serde_json::to_string(opportunity) {
+                            Ok(json) => println!("{}", json),
+                            Err(e) => warn!("Failed to serialize opportunity for block {}: {}", block_number, e),
+                        }
+                    }
+                }
+                Err(e) => warn!("Scan at block {} failed: {}", block_number, e),
+            }
+        }
+
+        warn!("Block header subscription ended; reconnecting in {:?}", backoff);
+        tokio::time::sleep(backoff).await;
+        backoff = std::cmp::min(backoff * 2, MAX_BACKOFF);
+    }
+}
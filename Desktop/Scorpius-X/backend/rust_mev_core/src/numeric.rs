@@ -0,0 +1,96 @@
+//! Precision-safe numeric conversions between `f64` and `U256`.
+//!
+//! Fiat-price math (`convert_eth_cost_to_base`, `calculate_usd_value`)
+//! used to round-trip prices through a decimal string via `parse_units`,
+//! which loses precision and can produce `inf`/overflow or a
+//! division-by-zero denominator for extreme prices. `u256_from_f64_saturating`
+//! converts exactly via IEEE-754 mantissa/exponent decomposition instead.
+
+use ethers::types::U256;
+
+/// Converts `x` to `U256` by decomposing the IEEE-754 double into its
+/// mantissa and exponent and shifting rather than formatting through a
+/// decimal string. Non-finite and negative inputs saturate to zero;
+/// values at or above `U256::MAX` saturate to `U256::MAX`.
+pub(crate) fn u256_from_f64_saturating(x: f64) -> U256 {
+    if !x.is_finite() || x <= 0.0 {
+        return U256::zero();
+    }
+
+    // U256::MAX rounds to 2^256 in f64 (the nearest representable value
+    // at this magnitude), so this comparison is the correct saturation
+    // boundary for "would overflow 256 bits".
+    if x >= MAX_U256_AS_F64 {
+        return U256::MAX;
+    }
+
+    let bits = x.to_bits();
+    let raw_exponent = ((bits >> 52) & 0x7ff) as i64;
+    let raw_mantissa = bits & 0x000f_ffff_ffff_ffff;
+
+    let (mantissa, exponent) = if raw_exponent == 0 {
+        // Subnormal: no implicit leading bit; unbiased exponent is -1022.
+        (raw_mantissa, -1022 - 52)
+    } else {
+        // Normal: restore the implicit leading bit.
+        (raw_mantissa | 0x0010_0000_0000_0000, raw_exponent - 1023 - 52)
+    };
+    let mantissa = U256::from(mantissa);
+
+    if exponent >= 0 {
+        let shift = exponent as usize;
+        if shift >= 256 {
+            U256::MAX
+        } else {
+            mantissa << shift
+        }
+    } else {
+        let shift = (-exponent) as usize;
+        if shift >= 256 {
+            U256::zero()
+        } else {
+            mantissa >> shift
+        }
+    }
+}
+
+const MAX_U256_AS_F64: f64 = 115_792_089_237_316_195_423_570_985_008_687_907_853_269_984_665_640_564_039_457_584_007_913_129_639_936.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_and_negative_and_non_finite_saturate_to_zero() {
+        assert_eq!(u256_from_f64_saturating(0.0), U256::zero());
+        assert_eq!(u256_from_f64_saturating(-1.0), U256::zero());
+        assert_eq!(u256_from_f64_saturating(f64::NAN), U256::zero());
+        assert_eq!(u256_from_f64_saturating(f64::INFINITY), U256::zero());
+        assert_eq!(u256_from_f64_saturating(f64::NEG_INFINITY), U256::zero());
+    }
+
+    #[test]
+    fn small_integers_convert_exactly() {
+        assert_eq!(u256_from_f64_saturating(1.0), U256::from(1));
+        assert_eq!(u256_from_f64_saturating(1_000_000_000_000_000_000.0), U256::from(10).pow(U256::from(18)));
+    }
+
+    #[test]
+    fn subnormal_inputs_truncate_to_zero() {
+        assert_eq!(u256_from_f64_saturating(f64::from_bits(1)), U256::zero());
+    }
+
+    #[test]
+    fn very_large_prices_saturate_to_u256_max() {
+        assert_eq!(u256_from_f64_saturating(f64::MAX), U256::MAX);
+        assert_eq!(u256_from_f64_saturating(MAX_U256_AS_F64 * 2.0), U256::MAX);
+    }
+
+    #[test]
+    fn value_just_below_u256_max_converts_without_saturating() {
+        // 2^255 is exactly representable and the largest power of two below
+        // the U256::MAX cutoff.
+        let x = 2f64.powi(255);
+        assert_eq!(u256_from_f64_saturating(x), U256::from(1) << 255);
+    }
+}
@@ -0,0 +1,486 @@
+//! Pluggable swap-quote backend for `simulate_swap_step`.
+//!
+//! `RpcSimulator` quotes each hop with a live `eth_call` - the original
+//! behavior. `EvmSimulator` instead replays the quote against a local
+//! `revm` executor loaded from a per-block state snapshot: a real scan
+//! takes far more quotes per block than it takes blocks, and every quote
+//! taken during one scan needs to read the same block for the resulting
+//! profit math to stay consistent.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use ethers::abi::{self, Token as AbiToken};
+use ethers::types::{Address, Bytes, U256 as EthersU256};
+use eyre::{eyre, Result};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{
+    AccountInfo, Bytecode, ExecutionResult, Output, TransactTo, B160, U256 as RevmU256,
+};
+use revm::Evm;
+use tokio::sync::Mutex;
+
+use crate::{get_amount_out_v2_local, get_v2_pool_data, Client, IQuoterV2};
+
+/// Quotes a single hop's output amount. `fee` distinguishes a V3 hop
+/// (`Some(fee_tier)`) from a V2 hop (`None`); `pool_address` is the
+/// already-resolved pair/pool to quote against, since resolving it is
+/// cheap and handled separately from the (potentially expensive) quote
+/// itself.
+#[async_trait::async_trait]
+pub(crate) trait SwapSimulator: Send + Sync {
+    async fn amount_out(
+        &self,
+        amount_in: EthersU256,
+        t_in: Address,
+        t_out: Address,
+        fee: Option<u32>,
+        pool_address: Address,
+    ) -> Result<EthersU256>;
+}
+
+/// Quotes every hop with a live RPC call, exactly as `simulate_swap_step`
+/// did before the `SwapSimulator` trait existed.
+pub(crate) struct RpcSimulator {
+    client: Client,
+    quoter: Option<Address>,
+}
+
+impl RpcSimulator {
+    pub(crate) fn new(client: Client, quoter: Option<Address>) -> Self {
+        Self { client, quoter }
+    }
+}
+
+#[async_trait::async_trait]
+impl SwapSimulator for RpcSimulator {
+    async fn amount_out(
+        &self,
+        amount_in: EthersU256,
+        t_in: Address,
+        t_out: Address,
+        fee: Option<u32>,
+        pool_address: Address,
+    ) -> Result<EthersU256> {
+        match fee {
+            Some(fee) => {
+                let quoter = self
+                    .quoter
+                    .ok_or_else(|| eyre!("RpcSimulator has no quoter configured for this dex"))?;
+                crate::get_amount_out_v3_quote(amount_in, t_in, t_out, fee, quoter, self.client.clone()).await
+            }
+            None => {
+                let (pool_data, _) = get_v2_pool_data(pool_address, self.client.clone())
+                    .await?
+                    .ok_or_else(|| eyre!("V2 pool data unavailable for {}", pool_address))?;
+                let (reserve_in, reserve_out) = if t_in == pool_data.token0 {
+                    (pool_data.reserve0, pool_data.reserve1)
+                } else {
+                    (pool_data.reserve1, pool_data.reserve0)
+                };
+                Ok(get_amount_out_v2_local(amount_in, reserve_in, reserve_out))
+            }
+        }
+    }
+}
+
+/// Code + storage slots read from the chain for one account, cached so
+/// later quotes through the same simulator against the same address don't
+/// re-fetch it.
+#[derive(Clone, Default)]
+struct AccountSnapshot {
+    code: Bytes,
+    storage: HashMap<EthersU256, EthersU256>,
+}
+
+/// All accounts loaded so far for one block. Every quote in a scan must
+/// read the same block, so this snapshot is pinned to `block_number` and
+/// refuses to mix in state from any other block.
+struct BlockSnapshot {
+    block_number: u64,
+    accounts: HashMap<Address, AccountSnapshot>,
+}
+
+/// Quotes hops by executing the relevant contract call against a local
+/// `revm` instance instead of round-tripping an `eth_call` to the node.
+/// State (code + touched storage slots) is fetched on first use and then
+/// cached for the lifetime of this simulator, all pinned to the block the
+/// scan started on. `build_simulator` makes a new simulator per hop, so
+/// the cache is not carried from one hop to the next.
+pub(crate) struct EvmSimulator {
+    client: Client,
+    quoter: Option<Address>,
+    block_number: u64,
+    snapshot: Mutex<Option<BlockSnapshot>>,
+}
+
+impl EvmSimulator {
+    pub(crate) fn new(client: Client, quoter: Option<Address>, block_number: u64) -> Self {
+        Self {
+            client,
+            quoter,
+            block_number,
+            snapshot: Mutex::new(None),
+        }
+    }
+
+    /// Ensures `address`'s code and the given storage `slots` are present
+    /// in the snapshot, fetching from `self.block_number` on first use.
+    /// Errors if an existing snapshot was built for a different block -
+    /// every quote in one scan must agree on the block it reads.
+    async fn ensure_loaded(&self, address: Address, slots: &[EthersU256]) -> Result<()> {
+        let mut guard = self.snapshot.lock().await;
+        if let Some(snapshot) = guard.as_ref() {
+            if snapshot.block_number != self.block_number {
+                return Err(eyre!(
+                    "EvmSimulator snapshot pinned to block {} but this simulator targets block {}",
+                    snapshot.block_number,
+                    self.block_number
+                ));
+            }
+        } else {
+            *guard = Some(BlockSnapshot {
+                block_number: self.block_number,
+                accounts: HashMap::new(),
+            });
+        }
+
+        let needs_code = !guard
+            .as_ref()
+            .unwrap()
+            .accounts
+            .get(&address)
+            .is_some_and(|a| !a.code.0.is_empty());
+        let code = if needs_code {
+            Some(
+                self.client
+                    .get_code(address, Some(self.block_number.into()))
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        let mut fetched_slots = Vec::new();
+        for &slot in slots {
+            let already_loaded = guard
+                .as_ref()
+                .unwrap()
+                .accounts
+                .get(&address)
+                .is_some_and(|a| a.storage.contains_key(&slot));
+            if !already_loaded {
+                let value = self
+                    .client
+                    .get_storage_at(address, slot_to_h256(slot), Some(self.block_number.into()))
+                    .await?;
+                fetched_slots.push((slot, EthersU256::from_big_endian(value.as_bytes())));
+            }
+        }
+
+        let snapshot = guard.as_mut().unwrap();
+        let entry = snapshot.accounts.entry(address).or_default();
+        if let Some(code) = code {
+            entry.code = code;
+        }
+        for (slot, value) in fetched_slots {
+            entry.storage.insert(slot, value);
+        }
+        Ok(())
+    }
+
+    /// Reads a single storage slot already fetched into the snapshot by an
+    /// earlier `ensure_loaded` call for the same address.
+    async fn read_storage(&self, address: Address, slot: EthersU256) -> Result<EthersU256> {
+        let guard = self.snapshot.lock().await;
+        guard
+            .as_ref()
+            .and_then(|s| s.accounts.get(&address))
+            .and_then(|a| a.storage.get(&slot))
+            .copied()
+            .ok_or_else(|| eyre!("storage slot {} not loaded for {}", slot, address))
+    }
+
+    /// Builds a `revm` instance seeded from the cached snapshot accounts.
+    async fn build_evm(&self) -> Result<Evm<'static, (), CacheDB<EmptyDB>>> {
+        let guard = self.snapshot.lock().await;
+        let snapshot = guard
+            .as_ref()
+            .ok_or_else(|| eyre!("EvmSimulator snapshot not loaded"))?;
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        for (address, account) in &snapshot.accounts {
+            let bytecode = Bytecode::new_raw(account.code.0.clone());
+            let info = AccountInfo {
+                code_hash: bytecode.hash_slow(),
+                code: Some(bytecode),
+                ..Default::default()
+            };
+            let b160_address = to_b160(*address);
+            db.insert_account_info(b160_address, info);
+            for (slot, value) in &account.storage {
+                db.insert_account_storage(b160_address, to_revm_u256(*slot), to_revm_u256(*value))?;
+            }
+        }
+
+        Ok(Evm::builder().with_db(db).build())
+    }
+
+    async fn v2_amount_out(
+        &self,
+        amount_in: EthersU256,
+        t_in: Address,
+        pool_address: Address,
+    ) -> Result<EthersU256> {
+        // `IUniswapV2Pair`'s `reserve0`/`reserve1`/`token0` live packed in
+        // storage slot 8 and slot 6 respectively - read both plus the
+        // contract's code, then replay the same constant-product formula
+        // `get_amount_out_v2_local` already uses for the RPC path.
+        const RESERVES_SLOT: u64 = 8;
+        const TOKEN0_SLOT: u64 = 6;
+        self.ensure_loaded(
+            pool_address,
+            &[EthersU256::from(RESERVES_SLOT), EthersU256::from(TOKEN0_SLOT)],
+        )
+        .await?;
+
+        let (token0, reserve0, reserve1) = {
+            let guard = self.snapshot.lock().await;
+            let account = guard
+                .as_ref()
+                .and_then(|s| s.accounts.get(&pool_address))
+                .ok_or_else(|| eyre!("V2 pool snapshot missing for {}", pool_address))?;
+
+            let token0_word = account
+                .storage
+                .get(&EthersU256::from(TOKEN0_SLOT))
+                .copied()
+                .unwrap_or_default();
+            let mut token0_bytes = [0u8; 32];
+            token0_word.to_big_endian(&mut token0_bytes);
+            let token0 = Address::from_slice(&token0_bytes[12..]);
+
+            let reserves_word = account
+                .storage
+                .get(&EthersU256::from(RESERVES_SLOT))
+                .copied()
+                .unwrap_or_default();
+            let mut reserves_bytes = [0u8; 32];
+            reserves_word.to_big_endian(&mut reserves_bytes);
+            // Packed as: [4 bytes blockTimestampLast | 14 bytes reserve1 (uint112) | 14 bytes reserve0 (uint112)]
+            let reserve1 = EthersU256::from_big_endian(&reserves_bytes[4..18]);
+            let reserve0 = EthersU256::from_big_endian(&reserves_bytes[18..32]);
+            (token0, reserve0, reserve1)
+        };
+
+        let (reserve_in, reserve_out) = if t_in == token0 {
+            (reserve0, reserve1)
+        } else {
+            (reserve1, reserve0)
+        };
+        Ok(get_amount_out_v2_local(amount_in, reserve_in, reserve_out))
+    }
+
+    async fn v3_amount_out(
+        &self,
+        amount_in: EthersU256,
+        t_in: Address,
+        t_out: Address,
+        fee: u32,
+        pool_address: Address,
+    ) -> Result<EthersU256> {
+        let quoter = self
+            .quoter
+            .ok_or_else(|| eyre!("EvmSimulator has no quoter configured for this dex"))?;
+
+        // The quoter itself has no persistent state worth caching - only
+        // its bytecode - while the pool it calls into needs its live slot0
+        // tick/sqrtPrice along with it; loading both lets the quoter's own
+        // internal call into the pool resolve against the snapshot instead
+        // of an empty account.
+        const SLOT0_SLOT: u64 = 0;
+        const LIQUIDITY_SLOT: u64 = 4;
+        self.ensure_loaded(quoter, &[]).await?;
+        self.ensure_loaded(
+            pool_address,
+            &[EthersU256::from(SLOT0_SLOT), EthersU256::from(LIQUIDITY_SLOT)],
+        )
+        .await?;
+
+        // `slot0`/`liquidity` alone only cover a quote that stays within
+        // the pool's current initialized tick range - a swap large enough
+        // to cross a tick boundary also needs that tick's bitmap word and
+        // `Tick.Info` mapping entry, which `tickSpacing` (derived from
+        // `fee`) and the current tick (from the `slot0` just loaded) let
+        // us compute and pre-load for a bounded neighborhood around the
+        // current price, without needing to execute the swap first.
+        let slot0 = self.read_storage(pool_address, EthersU256::from(SLOT0_SLOT)).await?;
+        let current_tick = decode_tick_from_slot0(slot0);
+        let tick_spacing = tick_spacing_for_fee(fee)
+            .ok_or_else(|| eyre!("unsupported V3 fee tier: {}", fee))?;
+        let word_pos = tick_bitmap_word(current_tick, tick_spacing);
+
+        // One word either side of the current price's own word covers a
+        // reasonable range of nearby crossings without having to guess how
+        // far an arbitrarily large swap might travel; anything that moves
+        // the price beyond this window is caught below by
+        // `initializedTicksCrossed` rather than silently mispriced.
+        let bitmap_slots: Vec<EthersU256> = (word_pos - 1..=word_pos + 1)
+            .map(tick_bitmap_storage_slot)
+            .collect();
+        self.ensure_loaded(pool_address, &bitmap_slots).await?;
+
+        let calldata = IQuoterV2::new(quoter, self.client.clone())
+            .quote_exact_input_single(t_in, t_out, fee, amount_in, EthersU256::zero())
+            .calldata()
+            .ok_or_else(|| eyre!("failed to encode quoteExactInputSingle calldata"))?;
+
+        let mut evm = self.build_evm().await?;
+        evm.context.evm.env.tx.caller = to_b160(Address::zero());
+        evm.context.evm.env.tx.transact_to = TransactTo::Call(to_b160(quoter));
+        evm.context.evm.env.tx.data = calldata.0;
+
+        let result = evm
+            .transact()
+            .map_err(|e| eyre!("EVM quote simulation failed: {:?}", e))?;
+
+        let output = match result.result {
+            ExecutionResult::Success { output: Output::Call(bytes), .. } => bytes,
+            ExecutionResult::Success { .. } => {
+                return Err(eyre!("quoteExactInputSingle returned no call output"))
+            }
+            ExecutionResult::Revert { output, .. } => {
+                return Err(eyre!("quoteExactInputSingle reverted: {:?}", output))
+            }
+            ExecutionResult::Halt { reason, .. } => {
+                return Err(eyre!("quoteExactInputSingle halted: {:?}", reason))
+            }
+        };
+
+        // `quoteExactInputSingle` returns
+        // `(amountOut, sqrtPriceX96After, initializedTicksCrossed, gasEstimate)`.
+        // A nonzero `initializedTicksCrossed` means the swap walked past a
+        // tick whose `liquidityNet` we never loaded (bitmap words are
+        // pre-loaded above, but the individual `Tick.Info` entries aren't) -
+        // the EVM read that tick as uninitialized and kept applying the
+        // pre-crossing liquidity past it, so `amountOut` is silently wrong
+        // rather than failing. Surface that as an error instead of trading
+        // on it.
+        let decoded = abi::decode(
+            &[
+                abi::ParamType::Uint(256),
+                abi::ParamType::Uint(160),
+                abi::ParamType::Uint(32),
+                abi::ParamType::Uint(256),
+            ],
+            &output,
+        )
+        .map_err(|e| eyre!("failed to decode quoteExactInputSingle output: {}", e))?;
+        let mut fields = decoded.into_iter();
+        let (Some(AbiToken::Uint(amount_out)), Some(AbiToken::Uint(_)), Some(AbiToken::Uint(ticks_crossed))) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            return Err(eyre!("unexpected quoteExactInputSingle return shape"));
+        };
+
+        if !ticks_crossed.is_zero() {
+            return Err(eyre!(
+                "V3 quote for pool {} crossed {} initialized tick(s) outside the pre-loaded \
+                 snapshot - refusing to trade on a quote computed against incomplete storage",
+                pool_address,
+                ticks_crossed
+            ));
+        }
+
+        Ok(amount_out)
+    }
+}
+
+#[async_trait::async_trait]
+impl SwapSimulator for EvmSimulator {
+    async fn amount_out(
+        &self,
+        amount_in: EthersU256,
+        t_in: Address,
+        t_out: Address,
+        fee: Option<u32>,
+        pool_address: Address,
+    ) -> Result<EthersU256> {
+        match fee {
+            Some(fee) => self.v3_amount_out(amount_in, t_in, t_out, fee, pool_address).await,
+            None => self.v2_amount_out(amount_in, t_in, pool_address).await,
+        }
+    }
+}
+
+fn slot_to_h256(slot: EthersU256) -> ethers::types::H256 {
+    let mut bytes = [0u8; 32];
+    slot.to_big_endian(&mut bytes);
+    ethers::types::H256::from(bytes)
+}
+
+fn to_b160(address: Address) -> B160 {
+    B160::from_slice(address.as_bytes())
+}
+
+fn to_revm_u256(value: EthersU256) -> RevmU256 {
+    let mut bytes = [0u8; 32];
+    value.to_big_endian(&mut bytes);
+    RevmU256::from_be_bytes(bytes)
+}
+
+/// Decodes the current tick out of a packed V3 `slot0` word: `sqrtPriceX96`
+/// (uint160) occupies the low 160 bits, followed immediately by the
+/// current tick as a signed `int24`.
+fn decode_tick_from_slot0(slot0: EthersU256) -> i32 {
+    let raw = ((slot0 >> 160).low_u32()) & 0x00FF_FFFF;
+    if raw & 0x0080_0000 != 0 {
+        (raw | 0xFF00_0000) as i32
+    } else {
+        raw as i32
+    }
+}
+
+/// Uniswap V3's fixed tick spacing per fee tier (hundredths of a bip).
+fn tick_spacing_for_fee(fee: u32) -> Option<i32> {
+    match fee {
+        100 => Some(1),
+        500 => Some(10),
+        3000 => Some(60),
+        10000 => Some(200),
+        _ => None,
+    }
+}
+
+/// Which `tickBitmap` word a tick's bit lives in, per Uniswap's
+/// `TickBitmap.position`: compress the tick by `spacing` with floor
+/// division (rounding towards negative infinity, same as Solidity's
+/// division for a negative tick) and take the upper bits as the word index.
+fn tick_bitmap_word(tick: i32, spacing: i32) -> i32 {
+    let compressed = if tick < 0 && tick % spacing != 0 {
+        tick / spacing - 1
+    } else {
+        tick / spacing
+    };
+    compressed >> 8
+}
+
+/// Pool storage slot for `tickBitmap`, a `mapping(int16 => uint256)`.
+const TICK_BITMAP_SLOT: u64 = 6;
+
+/// Storage slot for `tickBitmap[word_pos]`: Solidity resolves a mapping
+/// slot as `keccak256(left_pad(key) ++ left_pad(base_slot))`, with the
+/// `int16` key sign-extended into its own 32-byte word.
+fn tick_bitmap_storage_slot(word_pos: i32) -> EthersU256 {
+    let word_pos = word_pos as i16;
+    let mut key_bytes = if word_pos < 0 { [0xFFu8; 32] } else { [0u8; 32] };
+    key_bytes[30..32].copy_from_slice(&word_pos.to_be_bytes());
+
+    let mut base_bytes = [0u8; 32];
+    EthersU256::from(TICK_BITMAP_SLOT).to_big_endian(&mut base_bytes);
+
+    let mut preimage = Vec::with_capacity(64);
+    preimage.extend_from_slice(&key_bytes);
+    preimage.extend_from_slice(&base_bytes);
+    EthersU256::from_big_endian(&ethers::utils::keccak256(preimage))
+}
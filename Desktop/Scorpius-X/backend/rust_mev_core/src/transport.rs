@@ -0,0 +1,154 @@
+//! Transport abstraction so the scanner can talk to a node over HTTP,
+//! WebSocket, or a local IPC socket without forking every pool fetcher
+//! and abigen binding per transport. `ClientTransport` is a single
+//! `JsonRpcClient`/`PubsubClient` implementation that dispatches to
+//! whichever concrete transport it was built from, so
+//! `Client = Arc<Provider<ClientTransport>>` slots into every existing
+//! `Provider<Http>` call site unchanged.
+
+use std::fmt;
+use std::pin::Pin;
+
+use async_trait::async_trait;
+use ethers::providers::{
+    Http, Ipc, JsonRpcClient, JsonRpcError, ProviderError, PubsubClient, RpcError, Ws,
+};
+use ethers::types::U256;
+use eyre::Result;
+use futures::Stream;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::value::RawValue;
+
+/// Which concrete transport a connection string resolves to: `ws://`/
+/// `wss://` selects WebSocket, `http://`/`https://` selects HTTP, and
+/// anything else is treated as a filesystem path to an IPC socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransportKind {
+    Http,
+    Ws,
+    Ipc,
+}
+
+pub(crate) fn guess_transport_kind(endpoint: &str) -> TransportKind {
+    if endpoint.starts_with("ws://") || endpoint.starts_with("wss://") {
+        TransportKind::Ws
+    } else if endpoint.starts_with("http://") || endpoint.starts_with("https://") {
+        TransportKind::Http
+    } else {
+        TransportKind::Ipc
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ClientTransport {
+    Http(Http),
+    Ws(Ws),
+    Ipc(Ipc),
+}
+
+impl ClientTransport {
+    pub(crate) async fn connect(endpoint: &str) -> Result<Self> {
+        match guess_transport_kind(endpoint) {
+            TransportKind::Http => Ok(ClientTransport::Http(Http::new(endpoint.parse()?))),
+            TransportKind::Ws => Ok(ClientTransport::Ws(Ws::connect(endpoint).await?)),
+            TransportKind::Ipc => Ok(ClientTransport::Ipc(Ipc::connect(endpoint).await?)),
+        }
+    }
+
+    /// `Http` has no push channel, so `run_loop` returns an error for it
+    /// rather than calling `subscribe_blocks` on it.
+    pub(crate) fn supports_subscriptions(&self) -> bool {
+        !matches!(self, ClientTransport::Http(_))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum ClientTransportError {
+    Http(<Http as JsonRpcClient>::Error),
+    Ws(<Ws as JsonRpcClient>::Error),
+    Ipc(<Ipc as JsonRpcClient>::Error),
+}
+
+impl fmt::Display for ClientTransportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientTransportError::Http(e) => write!(f, "{}", e),
+            ClientTransportError::Ws(e) => write!(f, "{}", e),
+            ClientTransportError::Ipc(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientTransportError {}
+
+impl From<ClientTransportError> for ProviderError {
+    fn from(err: ClientTransportError) -> Self {
+        match err {
+            ClientTransportError::Http(e) => e.into(),
+            ClientTransportError::Ws(e) => e.into(),
+            ClientTransportError::Ipc(e) => e.into(),
+        }
+    }
+}
+
+impl RpcError for ClientTransportError {
+    fn as_error_response(&self) -> Option<&JsonRpcError> {
+        match self {
+            ClientTransportError::Http(e) => e.as_error_response(),
+            ClientTransportError::Ws(e) => e.as_error_response(),
+            ClientTransportError::Ipc(e) => e.as_error_response(),
+        }
+    }
+
+    fn as_serde_error(&self) -> Option<&serde_json::Error> {
+        match self {
+            ClientTransportError::Http(e) => e.as_serde_error(),
+            ClientTransportError::Ws(e) => e.as_serde_error(),
+            ClientTransportError::Ipc(e) => e.as_serde_error(),
+        }
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for ClientTransport {
+    type Error = ClientTransportError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> Result<R, Self::Error>
+    where
+        T: fmt::Debug + Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        match self {
+            ClientTransport::Http(inner) => inner.request(method, params).await.map_err(ClientTransportError::Http),
+            ClientTransport::Ws(inner) => inner.request(method, params).await.map_err(ClientTransportError::Ws),
+            ClientTransport::Ipc(inner) => inner.request(method, params).await.map_err(ClientTransportError::Ipc),
+        }
+    }
+}
+
+type BoxedNotificationStream = Pin<Box<dyn Stream<Item = Box<RawValue>> + Send>>;
+
+impl PubsubClient for ClientTransport {
+    type NotificationStream = BoxedNotificationStream;
+
+    fn subscribe<T: Into<U256>>(&self, id: T) -> Result<Self::NotificationStream, Self::Error> {
+        match self {
+            ClientTransport::Ws(inner) => Ok(Box::pin(inner.subscribe(id).map_err(ClientTransportError::Ws)?)),
+            ClientTransport::Ipc(inner) => Ok(Box::pin(inner.subscribe(id).map_err(ClientTransportError::Ipc)?)),
+            ClientTransport::Http(_) => {
+                unreachable!("PubsubClient::subscribe called on an Http transport - gate callers on supports_subscriptions()")
+            }
+        }
+    }
+
+    fn unsubscribe<T: Into<U256>>(&self, id: T) -> Result<(), Self::Error> {
+        match self {
+            ClientTransport::Ws(inner) => inner.unsubscribe(id).map_err(ClientTransportError::Ws),
+            ClientTransport::Ipc(inner) => inner.unsubscribe(id).map_err(ClientTransportError::Ipc),
+            ClientTransport::Http(_) => {
+                unreachable!("PubsubClient::unsubscribe called on an Http transport - gate callers on supports_subscriptions()")
+            }
+        }
+    }
+}